@@ -0,0 +1,395 @@
+use anchor_lang::prelude::*;
+
+// Highest platform fee the admin can set (10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
+pub const MAX_ARBITERS: usize = 20;
+
+// Jury defaults, adjustable by the admin
+pub const DEFAULT_MIN_JUROR_STAKE: u64 = 1_000_000_000; // 1 SOL
+pub const DEFAULT_JUROR_FEE: u64 = 10_000_000; // 0.01 SOL per juror
+pub const DEFAULT_JUROR_SLASH_BPS: u16 = 1_000;
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey, // Super-admin: manages fees, treasury and arbiters
+    pub pending_admin: Option<Pubkey>, // Proposed admin, until they accept the transfer
+    pub arbiters: Vec<Pubkey>, // Accounts allowed to rule on disputes
+    pub treasury: Pubkey, // Receives SOL fees; its ATAs receive SPL fees
+    pub fee_bps: u16, // Platform fee on every payout, in basis points (1% = 100)
+    pub min_juror_stake: u64, // Lamports a juror must stake to join the pool
+    pub juror_fee: u64, // Lamports paid to each juror by the party requesting a jury
+    pub juror_slash_bps: u16, // Share of stake slashed from jurors who do not reveal
+    pub appeal_window: i64, // Seconds an arbiter ruling can be appealed before it pays out; 0 disables appeals
+    pub appeal_bond: u64, // Lamports the appellant locks, refunded only if the ruling is overturned
+    pub dispute_bond_bps: u16, // Bond a dispute initiator locks, as a share of the disputed funds
+    pub bond_to_counterparty: bool, // Forfeited dispute bonds go to the other party instead of the treasury
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl Config {
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // admin
+        1 + 32 + // pending_admin
+        4 + (32 * MAX_ARBITERS) + // arbiters
+        32 + // treasury
+        2 + // fee_bps
+        8 + // min_juror_stake
+        8 + // juror_fee
+        2 + // juror_slash_bps
+        8 + // appeal_window
+        8 + // appeal_bond
+        2 + // dispute_bond_bps
+        1 + // bond_to_counterparty
+        8 + // created_at
+        8 + // updated_at
+        1; // bump
+
+    // Platform fee owed on a payout of `amount`
+    pub fn fee_for(&self, amount: u64) -> Result<u64> {
+        let fee = (amount as u128)
+            .checked_mul(self.fee_bps as u128)
+            .ok_or(ConfigError::MathOverflow)?
+            / 10_000;
+        Ok(fee as u64)
+    }
+
+    // Bond a party must lock to dispute `amount`
+    pub fn dispute_bond_for(&self, amount: u64) -> Result<u64> {
+        let bond = (amount as u128)
+            .checked_mul(self.dispute_bond_bps as u128)
+            .ok_or(ConfigError::MathOverflow)?
+            / 10_000;
+        Ok(bond as u64)
+    }
+
+    pub fn is_arbiter(&self, key: &Pubkey) -> bool {
+        self.arbiters.contains(key)
+    }
+}
+
+// Only the program's upgrade authority can create the config and become its admin
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = Config::SPACE,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ ConfigError::Unauthorized)]
+    pub program: Program<'info, crate::program::FreelanceMarketplace>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ConfigError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ConfigError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+// Context for the proposed admin accepting the transfer
+#[derive(Accounts)]
+pub struct AcceptAdminTransfer<'info> {
+    pub new_admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.pending_admin == Some(new_admin.key()) @ ConfigError::NotPendingAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[event]
+pub struct ConfigInitialized {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct JuryConfigUpdated {
+    pub min_juror_stake: u64,
+    pub juror_fee: u64,
+    pub juror_slash_bps: u16,
+}
+
+#[event]
+pub struct DisputeConfigUpdated {
+    pub appeal_window: i64,
+    pub appeal_bond: u64,
+    pub dispute_bond_bps: u16,
+    pub bond_to_counterparty: bool,
+}
+
+#[event]
+pub struct ArbiterAdded {
+    pub arbiter: Pubkey,
+    pub arbiter_count: u32,
+}
+
+#[event]
+pub struct ArbiterRemoved {
+    pub arbiter: Pubkey,
+    pub arbiter_count: u32,
+}
+
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub previous_admin: Pubkey,
+    pub admin: Pubkey,
+}
+
+pub fn initialize_config(
+    ctx: Context<InitializeConfig>,
+    treasury: Pubkey,
+    fee_bps: u16,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    require!(fee_bps <= MAX_FEE_BPS, ConfigError::FeeTooHigh);
+
+    config.admin = ctx.accounts.admin.key();
+    config.pending_admin = None;
+    config.arbiters = Vec::new();
+    config.treasury = treasury;
+    config.fee_bps = fee_bps;
+    config.min_juror_stake = DEFAULT_MIN_JUROR_STAKE;
+    config.juror_fee = DEFAULT_JUROR_FEE;
+    config.juror_slash_bps = DEFAULT_JUROR_SLASH_BPS;
+    config.appeal_window = 0;
+    config.appeal_bond = 0;
+    config.dispute_bond_bps = 0;
+    config.bond_to_counterparty = false;
+    config.created_at = clock.unix_timestamp;
+    config.updated_at = clock.unix_timestamp;
+    config.bump = ctx.bumps.config;
+
+    emit!(ConfigInitialized {
+        admin: config.admin,
+        treasury,
+        fee_bps,
+    });
+
+    Ok(())
+}
+
+pub fn update_config(
+    ctx: Context<UpdateConfig>,
+    treasury: Option<Pubkey>,
+    fee_bps: Option<u16>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    // Update fields if provided
+    if let Some(new_treasury) = treasury {
+        config.treasury = new_treasury;
+    }
+
+    if let Some(new_fee_bps) = fee_bps {
+        require!(new_fee_bps <= MAX_FEE_BPS, ConfigError::FeeTooHigh);
+        config.fee_bps = new_fee_bps;
+    }
+
+    config.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(ConfigUpdated {
+        admin: config.admin,
+        treasury: config.treasury,
+        fee_bps: config.fee_bps,
+    });
+
+    Ok(())
+}
+
+pub fn update_jury_config(
+    ctx: Context<UpdateConfig>,
+    min_juror_stake: Option<u64>,
+    juror_fee: Option<u64>,
+    juror_slash_bps: Option<u16>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    // Update fields if provided
+    if let Some(new_min_juror_stake) = min_juror_stake {
+        config.min_juror_stake = new_min_juror_stake;
+    }
+
+    if let Some(new_juror_fee) = juror_fee {
+        config.juror_fee = new_juror_fee;
+    }
+
+    if let Some(new_juror_slash_bps) = juror_slash_bps {
+        require!(new_juror_slash_bps <= 10_000, ConfigError::InvalidSlashBps);
+        config.juror_slash_bps = new_juror_slash_bps;
+    }
+
+    config.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(JuryConfigUpdated {
+        min_juror_stake: config.min_juror_stake,
+        juror_fee: config.juror_fee,
+        juror_slash_bps: config.juror_slash_bps,
+    });
+
+    Ok(())
+}
+
+pub fn update_dispute_config(
+    ctx: Context<UpdateConfig>,
+    appeal_window: Option<i64>,
+    appeal_bond: Option<u64>,
+    dispute_bond_bps: Option<u16>,
+    bond_to_counterparty: Option<bool>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    // Update fields if provided
+    if let Some(new_appeal_window) = appeal_window {
+        require!(new_appeal_window >= 0, ConfigError::InvalidAppealWindow);
+        config.appeal_window = new_appeal_window;
+    }
+
+    if let Some(new_appeal_bond) = appeal_bond {
+        config.appeal_bond = new_appeal_bond;
+    }
+
+    if let Some(new_dispute_bond_bps) = dispute_bond_bps {
+        require!(new_dispute_bond_bps <= 10_000, ConfigError::InvalidBondBps);
+        config.dispute_bond_bps = new_dispute_bond_bps;
+    }
+
+    if let Some(new_bond_to_counterparty) = bond_to_counterparty {
+        config.bond_to_counterparty = new_bond_to_counterparty;
+    }
+
+    config.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(DisputeConfigUpdated {
+        appeal_window: config.appeal_window,
+        appeal_bond: config.appeal_bond,
+        dispute_bond_bps: config.dispute_bond_bps,
+        bond_to_counterparty: config.bond_to_counterparty,
+    });
+
+    Ok(())
+}
+
+pub fn add_arbiter(ctx: Context<UpdateConfig>, arbiter: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    require!(!config.is_arbiter(&arbiter), ConfigError::ArbiterAlreadyRegistered);
+    require!(config.arbiters.len() < MAX_ARBITERS, ConfigError::TooManyArbiters);
+
+    config.arbiters.push(arbiter);
+    config.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(ArbiterAdded {
+        arbiter,
+        arbiter_count: config.arbiters.len() as u32,
+    });
+
+    Ok(())
+}
+
+pub fn remove_arbiter(ctx: Context<UpdateConfig>, arbiter: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    let position = config
+        .arbiters
+        .iter()
+        .position(|registered| *registered == arbiter)
+        .ok_or(ConfigError::ArbiterNotFound)?;
+    config.arbiters.remove(position);
+    config.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(ArbiterRemoved {
+        arbiter,
+        arbiter_count: config.arbiters.len() as u32,
+    });
+
+    Ok(())
+}
+
+// First step of an admin transfer; the new admin has to accept before it takes effect
+pub fn propose_admin_transfer(ctx: Context<UpdateConfig>, new_admin: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    config.pending_admin = Some(new_admin);
+    config.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(AdminTransferProposed {
+        admin: config.admin,
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
+
+pub fn accept_admin_transfer(ctx: Context<AcceptAdminTransfer>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let previous_admin = config.admin;
+
+    config.admin = ctx.accounts.new_admin.key();
+    config.pending_admin = None;
+    config.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(AdminTransferred {
+        previous_admin,
+        admin: config.admin,
+    });
+
+    Ok(())
+}
+
+#[error_code]
+pub enum ConfigError {
+    #[msg("Only the admin can perform this action")]
+    Unauthorized,
+    #[msg("Platform fee exceeds the maximum")]
+    FeeTooHigh,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Arbiter is already registered")]
+    ArbiterAlreadyRegistered,
+    #[msg("Arbiter is not registered")]
+    ArbiterNotFound,
+    #[msg("Maximum number of arbiters reached")]
+    TooManyArbiters,
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    #[msg("Slash share cannot exceed 10000 basis points")]
+    InvalidSlashBps,
+    #[msg("Appeal window cannot be negative")]
+    InvalidAppealWindow,
+    #[msg("Dispute bond cannot exceed 10000 basis points")]
+    InvalidBondBps,
+}
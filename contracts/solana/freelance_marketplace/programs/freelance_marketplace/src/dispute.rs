@@ -1,214 +1,1094 @@
 use anchor_lang::prelude::*;
-use crate::job::{Job, JobStatus};
-use crate::escrow::Escrow;
+use anchor_lang::system_program;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::job::{Job, JobStatus, MilestoneStatus};
+use crate::escrow::{milestone_amount, Escrow, EscrowDepositor, EscrowPayer, EscrowStatus, EscrowVault, SplitOffer};
+use crate::config::Config;
+
+pub const MAX_REASON_LEN: usize = 500;
+pub const MAX_EVIDENCE_URI_LEN: usize = 200;
+pub const MAX_EVIDENCE_DESCRIPTION_LEN: usize = 500;
+pub const MAX_EVIDENCE_PER_PARTY: u8 = 10;
+pub const EVIDENCE_WINDOW: i64 = 7 * 24 * 60 * 60; // 7 days
+pub const RESPONSE_PERIOD: i64 = 3 * 24 * 60 * 60; // 3 days for the respondent to answer
+pub const MAX_RESOLUTION_NOTE_LEN: usize = 200;
+pub const TOTAL_SPLIT_BPS: u16 = 10_000;
+// Seed scope of a dispute over the whole job; milestone disputes use the milestone index
+pub const WHOLE_JOB_SCOPE: u8 = u8::MAX;
+
+pub fn dispute_scope(milestone_index: Option<u8>) -> u8 {
+    milestone_index.unwrap_or(WHOLE_JOB_SCOPE)
+}
 
 #[account]
 pub struct Dispute {
+    pub job: Pubkey,
     pub job_id: u64,
     pub escrow: Pubkey,
     pub client: Pubkey,
     pub freelancer: Pubkey,
     pub initiator: Pubkey,
+    pub milestone_index: Option<u8>, // None disputes the whole job, Some only that milestone
+    pub disputed_amount: u64, // Escrowed funds the ruling can move
+    pub bond: u64, // Filing bond locked by the initiator
     pub reason: String,
-    pub evidence_ipfs: String,
+    pub response_deadline: i64, // The respondent must submit evidence by this time
+    pub responded: bool,
+    pub evidence_deadline: i64, // Both parties can submit evidence until this time
+    pub client_evidence_count: u8,
+    pub freelancer_evidence_count: u8,
     pub is_resolved: bool,
-    pub resolution_details: Option<ResolutionDetails>,
+    pub resolution_details: Option<ResolutionDetails>, // A pending ruling while the appeal window is open
+    pub appeal_deadline: Option<i64>, // Set while a ruling waits out its appeal window
+    pub appeal: Option<Appeal>,
+    pub settlement_offer: Option<SplitOffer>,
+    pub jury: Option<Pubkey>, // Set while a jury decides the dispute instead of an arbiter
     pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Dispute {
+    pub fn space(reason: &str) -> usize {
+        8 + // discriminator
+        32 + // job
+        8 + // job_id
+        32 + // escrow
+        32 + // client
+        32 + // freelancer
+        32 + // initiator
+        1 + 1 + // milestone_index
+        8 + // disputed_amount
+        8 + // bond
+        4 + reason.len() + // reason
+        8 + // response_deadline
+        1 + // responded
+        8 + // evidence_deadline
+        1 + // client_evidence_count
+        1 + // freelancer_evidence_count
+        1 + // is_resolved
+        1 + ResolutionDetails::SPACE + // resolution_details
+        1 + 8 + // appeal_deadline
+        1 + Appeal::SPACE + // appeal
+        1 + SplitOffer::SPACE + // settlement_offer
+        1 + 32 + // jury
+        8 + // created_at
+        1 // bump
+    }
+}
+
+impl Dispute {
+    pub fn scope(&self) -> u8 {
+        dispute_scope(self.milestone_index)
+    }
+
+    // The party the dispute was opened against
+    pub fn respondent(&self) -> Pubkey {
+        if self.initiator == self.client {
+            self.freelancer
+        } else {
+            self.client
+        }
+    }
+
+    // Number of evidence items a party has submitted so far
+    pub fn evidence_count(&self, party: &Pubkey) -> Result<u8> {
+        if *party == self.client {
+            Ok(self.client_evidence_count)
+        } else if *party == self.freelancer {
+            Ok(self.freelancer_evidence_count)
+        } else {
+            err!(DisputeError::InvalidParty)
+        }
+    }
+}
+
+// A single piece of evidence submitted by one of the parties
+#[account]
+pub struct Evidence {
+    pub dispute: Pubkey,
+    pub submitter: Pubkey,
+    pub index: u8, // Position in the submitter's own sequence
+    pub content_hash: [u8; 32], // Hash of the evidence content, so it cannot be swapped later
+    pub uri: String, // Where the content lives (IPFS, Arweave, ...)
+    pub description: String,
+    pub submitted_at: i64,
+    pub bump: u8,
+}
+
+impl Evidence {
+    pub fn space(uri: &str, description: &str) -> usize {
+        8 + // discriminator
+        32 + // dispute
+        32 + // submitter
+        1 + // index
+        32 + // content_hash
+        4 + uri.len() + // uri
+        4 + description.len() + // description
+        8 + // submitted_at
+        1 // bump
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct ResolutionDetails {
     pub resolved_by: Pubkey,
     pub resolution_type: ResolutionType,
-    pub split_ratio: Option<u64>, // If split, percentage to freelancer (0-100)
+    pub split_bps: Option<u16>, // If split, share of the remaining escrow to the freelancer in basis points
     pub resolution_note: String,
     pub resolved_at: i64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+impl ResolutionDetails {
+    pub const SPACE: usize = 32 + // resolved_by
+        1 + // resolution_type
+        1 + 2 + // split_bps
+        4 + MAX_RESOLUTION_NOTE_LEN + // resolution_note
+        8; // resolved_at
+
+    pub fn freelancer_bps(&self) -> Result<u16> {
+        self.resolution_type.freelancer_bps(self.split_bps.unwrap_or(0))
+    }
+}
+
+// An appealed ruling, waiting for a second arbiter
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Appeal {
+    pub appellant: Pubkey,
+    pub bond: u64, // Lamports held by the dispute account
+    pub first_arbiter: Pubkey,
+    pub first_freelancer_bps: u16,
+    pub appealed_at: i64,
+}
+
+impl Appeal {
+    pub const SPACE: usize = 32 + // appellant
+        8 + // bond
+        32 + // first_arbiter
+        2 + // first_freelancer_bps
+        8; // appealed_at
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum ResolutionType {
     ReleaseToFreelancer,
     RefundToClient,
     Split,
+    Settled, // Split agreed between the parties themselves
+}
+
+impl ResolutionType {
+    // Share of the remaining escrow that goes to the freelancer
+    pub fn freelancer_bps(&self, split_bps: u16) -> Result<u16> {
+        match self {
+            ResolutionType::ReleaseToFreelancer => Ok(TOTAL_SPLIT_BPS),
+            ResolutionType::RefundToClient => Ok(0),
+            ResolutionType::Split | ResolutionType::Settled => {
+                require!(split_bps <= TOTAL_SPLIT_BPS, DisputeError::InvalidSplitRatio);
+                Ok(split_bps)
+            }
+        }
+    }
 }
 
+// Context for opening a dispute on an in-progress job
 #[derive(Accounts)]
-#[instruction(job_id: u64, reason: String, evidence_ipfs: String)]
+#[instruction(job_id: u64, milestone_index: Option<u8>, reason: String)]
 pub struct InitiateDispute<'info> {
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"job", job_account.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job_account.bump,
+        constraint = job_account.escrow == Some(escrow_account.key()) @ DisputeError::InvalidEscrow,
+    )]
+    pub job_account: Account<'info, Job>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_account.key().as_ref()],
+        bump = escrow_account.bump,
+        constraint = escrow_account.status == EscrowStatus::Active @ DisputeError::EscrowNotActive,
+    )]
+    pub escrow_account: Account<'info, Escrow>,
     #[account(
         init,
         payer = initiator,
-        space = 8 + 8 + 32 + 32 + 32 + 32 + 4 + reason.len() + 4 + evidence_ipfs.len() + 1 + 200 + 8
+        space = Dispute::space(&reason),
+        seeds = [b"dispute", job_account.key().as_ref(), &dispute_scope(milestone_index).to_le_bytes()],
+        bump,
     )]
     pub dispute: Account<'info, Dispute>,
-    #[account(mut, constraint = job.job_id == job_id)]
-    pub job: Account<'info, Job>,
-    #[account(mut, constraint = escrow.job_id == job_id)]
-    pub escrow: Account<'info, Escrow>,
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_account.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, EscrowVault>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+    // Optional token accounts, for bonds on SPL escrows
+    pub token_program: Option<Program<'info, Token>>,
+    #[account(
+        mut,
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = escrow_account,
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = escrow_account.token_mint.unwrap(),
+        token::authority = initiator,
+    )]
+    pub initiator_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+// Context for a party adding evidence to an open dispute
+#[derive(Accounts)]
+#[instruction(index: u8, content_hash: [u8; 32], uri: String, description: String)]
+pub struct SubmitEvidence<'info> {
     #[account(mut)]
-    pub initiator: Signer<'info>,
+    pub submitter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.job.as_ref(), &dispute.scope().to_le_bytes()],
+        bump = dispute.bump,
+        constraint = !dispute.is_resolved @ DisputeError::AlreadyResolved,
+    )]
+    pub dispute: Account<'info, Dispute>,
+    #[account(
+        init,
+        payer = submitter,
+        space = Evidence::space(&uri, &description),
+        seeds = [b"evidence", dispute.key().as_ref(), submitter.key().as_ref(), &index.to_le_bytes()],
+        bump,
+    )]
+    pub evidence: Account<'info, Evidence>,
     pub system_program: Program<'info, System>,
 }
 
+// Accounts needed to pay out a disputed escrow
 #[derive(Accounts)]
-#[instruction(job_id: u64, release_to_freelancer: bool, split_ratio: u64)]
-pub struct ResolveDispute<'info> {
-    #[account(mut, constraint = dispute.job_id == job_id)]
+pub struct DisputePayout<'info> {
+    #[account(mut)]
+    pub job_account: Account<'info, Job>,
+    #[account(
+        mut,
+        seeds = [b"dispute", job_account.key().as_ref(), &dispute.scope().to_le_bytes()],
+        bump = dispute.bump,
+        constraint = !dispute.is_resolved @ DisputeError::AlreadyResolved,
+    )]
     pub dispute: Account<'info, Dispute>,
-    #[account(mut, constraint = job.job_id == job_id)]
-    pub job: Account<'info, Job>,
-    #[account(mut, constraint = escrow.job_id == job_id)]
-    pub escrow: Account<'info, Escrow>,
-    /// CHECK: This is safe as we validate it matches the job's client
-    #[account(mut, constraint = client.key() == job.client)]
+    #[account(
+        mut,
+        seeds = [b"escrow", job_account.key().as_ref()],
+        bump = escrow_account.bump,
+        constraint = escrow_account.key() == dispute.escrow @ DisputeError::InvalidEscrow,
+        constraint = escrow_account.status == EscrowStatus::Disputed
+            || dispute.milestone_index.is_some() @ DisputeError::EscrowNotDisputed,
+    )]
+    pub escrow_account: Account<'info, Escrow>,
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_account.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, EscrowVault>,
+    /// CHECK: This is safe as we validate it matches the dispute's client
+    #[account(mut, address = dispute.client @ DisputeError::InvalidParty)]
     pub client: AccountInfo<'info>,
-    /// CHECK: This is safe as we validate it matches the job's selected_freelancer
-    #[account(mut, constraint = freelancer.key() == job.selected_freelancer.unwrap())]
+    /// CHECK: This is safe as we validate it matches the dispute's freelancer
+    #[account(mut, address = dispute.freelancer @ DisputeError::InvalidParty)]
     pub freelancer: AccountInfo<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: This is safe as we validate it matches the config treasury
+    #[account(mut, address = config.treasury @ DisputeError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+    // Optional token accounts
+    pub token_program: Option<Program<'info, Token>>,
+    #[account(
+        mut,
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = escrow_account,
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = escrow_account.token_mint.unwrap(),
+        token::authority = client,
+    )]
+    pub client_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = escrow_account.token_mint.unwrap(),
+        token::authority = freelancer,
+    )]
+    pub freelancer_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = config.treasury,
+    )]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+// Context for the initiator winning by default when the respondent never answered
+#[derive(Accounts)]
+pub struct ClaimDefaultJudgment<'info> {
+    #[account(
+        constraint = initiator.key() == payout.dispute.initiator @ DisputeError::NotInitiator,
+        constraint = !payout.dispute.responded @ DisputeError::RespondentAnswered,
+        constraint = payout.dispute.jury.is_none() @ DisputeError::JuryAssigned,
+        constraint = payout.dispute.appeal_deadline.is_none()
+            && payout.dispute.appeal.is_none() @ DisputeError::RulingPending,
+    )]
+    pub initiator: Signer<'info>,
+    pub payout: DisputePayout<'info>,
+}
+
+// Amounts moved when a dispute is paid out
+pub struct DisputeSettlement {
+    pub freelancer_amount: u64,
+    pub client_amount: u64,
+    pub fee: u64,
+    pub bond_returned: u64,
+    pub bond_forfeited: u64,
+}
+
+impl<'info> DisputePayout<'info> {
+    // Splits the disputed funds: `freelancer_bps` of them to the freelancer (less the
+    // platform fee) and the rest back to the client. A whole-job dispute covers
+    // whatever the escrow still holds, so milestones already paid out are never paid
+    // twice; a milestone dispute only covers that milestone's tranche.
+    // A negotiated settlement carries no fee and returns the whole filing bond.
+    pub fn execute(&mut self, freelancer_bps: u16, negotiated: bool, now: i64) -> Result<DisputeSettlement> {
+        require!(freelancer_bps <= TOTAL_SPLIT_BPS, DisputeError::InvalidSplitRatio);
+
+        let escrow = &mut self.escrow_account;
+        let job = &mut self.job_account;
+        let milestone_index = self.dispute.milestone_index;
+
+        // Settling the last outstanding milestone completes the plan; like an
+        // approval, it takes whatever is left so the escrow ends at exactly zero
+        let milestone_count = job.milestones.as_ref().map_or(0, |milestones| milestones.len());
+        let completes_plan = milestone_index.is_some()
+            && escrow.completed_milestones.len() + 1 == milestone_count;
+
+        let disputed_amount = match milestone_index {
+            Some(_) if completes_plan => escrow.amount.saturating_sub(escrow.total_released),
+            Some(_) => self.dispute.disputed_amount,
+            None => escrow.available(),
+        };
+        let freelancer_amount = (disputed_amount as u128)
+            .checked_mul(freelancer_bps as u128)
+            .ok_or(DisputeError::MathOverflow)?
+            / TOTAL_SPLIT_BPS as u128;
+        let freelancer_amount = u64::try_from(freelancer_amount).map_err(|_| error!(DisputeError::MathOverflow))?;
+        let client_amount = disputed_amount
+            .checked_sub(freelancer_amount)
+            .ok_or(DisputeError::MathOverflow)?;
+
+        // Unlock the milestone's tranche so it can be paid out; the payout fails
+        // if the escrow no longer holds it
+        if milestone_index.is_some() {
+            escrow.locked_amount = escrow
+                .locked_amount
+                .checked_sub(self.dispute.disputed_amount)
+                .ok_or(DisputeError::MathOverflow)?;
+        }
+        escrow.record_payout(disputed_amount)?;
+        escrow.updated_at = now;
+        job.updated_at = now;
+
+        if let Some(index) = milestone_index {
+            // Only the disputed milestone is settled; the rest of the job carries on
+            escrow.completed_milestones.push(index);
+
+            let milestone = job
+                .milestones
+                .as_mut()
+                .and_then(|milestones| milestones.get_mut(index as usize))
+                .ok_or(DisputeError::InvalidMilestone)?;
+            milestone.status = MilestoneStatus::Resolved;
+
+            if completes_plan {
+                job.status = JobStatus::Completed;
+                job.completed_at = Some(now);
+                escrow.status = EscrowStatus::Released;
+            }
+        } else {
+            escrow.status = if freelancer_bps > 0 {
+                EscrowStatus::Released
+            } else {
+                EscrowStatus::Refunded
+            };
+
+            if freelancer_bps > 0 {
+                job.status = JobStatus::Completed;
+                job.completed_at = Some(now);
+            } else {
+                job.status = JobStatus::Cancelled;
+            }
+        }
+
+        // The platform fee is only charged on what the freelancer receives
+        let payer = EscrowPayer {
+            escrow: &self.escrow_account,
+            vault: &self.vault,
+            escrow_token_account: self.escrow_token_account.as_ref(),
+            token_program: self.token_program.as_ref(),
+        };
+        let fee = if negotiated {
+            payer.pay(&self.freelancer, self.freelancer_token_account.as_ref(), freelancer_amount)?;
+            0
+        } else {
+            payer.pay_with_fee(
+                &self.config,
+                &self.freelancer,
+                self.freelancer_token_account.as_ref(),
+                &self.treasury,
+                self.treasury_token_account.as_ref(),
+                freelancer_amount,
+            )?
+        };
+        payer.pay(&self.client, self.client_token_account.as_ref(), client_amount)?;
+
+        // The initiator gets back the share of the bond matching the share of the
+        // outcome that went their way; the rest is forfeited
+        let bond = self.dispute.bond;
+        let initiator_is_client = self.dispute.initiator == self.dispute.client;
+        let initiator_bps = if negotiated {
+            TOTAL_SPLIT_BPS
+        } else if initiator_is_client {
+            TOTAL_SPLIT_BPS - freelancer_bps
+        } else {
+            freelancer_bps
+        };
+        let bond_returned = (bond as u128)
+            .checked_mul(initiator_bps as u128)
+            .ok_or(DisputeError::MathOverflow)?
+            / TOTAL_SPLIT_BPS as u128;
+        let bond_returned = u64::try_from(bond_returned).map_err(|_| error!(DisputeError::MathOverflow))?;
+        let bond_forfeited = bond.checked_sub(bond_returned).ok_or(DisputeError::MathOverflow)?;
+
+        let (initiator, initiator_token_account, counterparty, counterparty_token_account) = if initiator_is_client {
+            (&self.client, self.client_token_account.as_ref(), &self.freelancer, self.freelancer_token_account.as_ref())
+        } else {
+            (&self.freelancer, self.freelancer_token_account.as_ref(), &self.client, self.client_token_account.as_ref())
+        };
+        let (forfeit_recipient, forfeit_token_account) = if self.config.bond_to_counterparty {
+            (counterparty, counterparty_token_account)
+        } else {
+            (&self.treasury, self.treasury_token_account.as_ref())
+        };
+
+        payer.pay(initiator, initiator_token_account, bond_returned)?;
+        payer.pay(forfeit_recipient, forfeit_token_account, bond_forfeited)?;
+
+        if bond > 0 {
+            emit!(DisputeBondSettled {
+                dispute: self.dispute.key(),
+                initiator: self.dispute.initiator,
+                bond,
+                returned: bond_returned,
+                forfeited: bond_forfeited,
+                forfeited_to: forfeit_recipient.key(),
+            });
+        }
+
+        self.escrow_account.dispute_bond = self
+            .escrow_account
+            .dispute_bond
+            .checked_sub(bond)
+            .ok_or(DisputeError::MathOverflow)?;
+        self.dispute.is_resolved = true;
+
+        Ok(DisputeSettlement {
+            freelancer_amount,
+            client_amount,
+            fee,
+            bond_returned,
+            bond_forfeited,
+        })
+    }
+
+    // Pays out a ruling and records it on the dispute
+    pub fn settle(&mut self, details: ResolutionDetails, now: i64) -> Result<DisputeSettlement> {
+        let resolution_type = details.resolution_type;
+        let negotiated = resolution_type == ResolutionType::Settled;
+        let settlement = self.execute(details.freelancer_bps()?, negotiated, now)?;
+
+        self.dispute.resolution_details = Some(details);
+        self.dispute.appeal_deadline = None;
+        self.dispute.settlement_offer = None;
+
+        emit!(DisputeResolved {
+            dispute: self.dispute.key(),
+            job_id: self.dispute.job_id,
+            resolution_type,
+            freelancer_amount: settlement.freelancer_amount,
+            client_amount: settlement.client_amount,
+            fee: settlement.fee,
+        });
+
+        Ok(settlement)
+    }
+}
+
+// Context for an arbiter ruling on a dispute
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        constraint = payout.config.is_arbiter(&arbiter.key()) @ DisputeError::UnauthorizedResolver,
+        constraint = arbiter.key() != payout.dispute.client
+            && arbiter.key() != payout.dispute.freelancer @ DisputeError::ArbiterIsParty,
+        constraint = payout.dispute.jury.is_none() @ DisputeError::JuryAssigned,
+        constraint = payout.dispute.appeal_deadline.is_none() @ DisputeError::RulingPending,
+    )]
+    pub arbiter: Signer<'info>,
+    pub payout: DisputePayout<'info>,
+}
+
+// Context for the losing party appealing a pending ruling
+#[derive(Accounts)]
+pub struct AppealRuling<'info> {
     #[account(mut)]
-    pub admin: Signer<'info>,
-    /// CHECK: This is safe as we're just sending platform fees
-    #[account(mut)]
-    pub platform_fee_account: AccountInfo<'info>,
-    /// CHECK: This is the admin account that should be verified
-    #[account(address = admin_account::ID)]
-    pub admin_account: AccountInfo<'info>,
+    pub appellant: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.job.as_ref(), &dispute.scope().to_le_bytes()],
+        bump = dispute.bump,
+        constraint = !dispute.is_resolved @ DisputeError::AlreadyResolved,
+        constraint = dispute.appeal.is_none() @ DisputeError::AlreadyAppealed,
+    )]
+    pub dispute: Account<'info, Dispute>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
     pub system_program: Program<'info, System>,
 }
 
-// Admin account ID (would be replaced with actual admin pubkey in production)
-pub mod admin_account {
-    use solana_program::pubkey::Pubkey;
-    use solana_program::pubkey;
-    pub static ID: Pubkey = pubkey!("AdminAccountPubkeyHere11111111111111111111111");
+// Context for a party offering to settle the dispute
+#[derive(Accounts)]
+pub struct ProposeSettlement<'info> {
+    pub proposer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.job.as_ref(), &dispute.scope().to_le_bytes()],
+        bump = dispute.bump,
+        constraint = !dispute.is_resolved @ DisputeError::AlreadyResolved,
+        constraint = dispute.jury.is_none() @ DisputeError::JuryAssigned,
+        constraint = proposer.key() == dispute.client
+            || proposer.key() == dispute.freelancer @ DisputeError::InvalidParty,
+    )]
+    pub dispute: Account<'info, Dispute>,
+}
+
+// Context for the other party accepting a settlement offer
+#[derive(Accounts)]
+pub struct AcceptSettlement<'info> {
+    #[account(
+        constraint = acceptor.key() == payout.dispute.client
+            || acceptor.key() == payout.dispute.freelancer @ DisputeError::InvalidParty,
+        constraint = payout.dispute.jury.is_none() @ DisputeError::JuryAssigned,
+    )]
+    pub acceptor: Signer<'info>,
+    pub payout: DisputePayout<'info>,
+}
+
+// Context for paying out a ruling once its appeal window has closed
+#[derive(Accounts)]
+pub struct ExecuteRuling<'info> {
+    pub payout: DisputePayout<'info>,
+}
+
+#[event]
+pub struct DisputeInitiated {
+    pub dispute: Pubkey,
+    pub job: Pubkey,
+    pub job_id: u64,
+    pub milestone_index: Option<u8>,
+    pub initiator: Pubkey,
+    pub disputed_amount: u64,
+    pub bond: u64,
+}
+
+#[event]
+pub struct DisputeBondSettled {
+    pub dispute: Pubkey,
+    pub initiator: Pubkey,
+    pub bond: u64,
+    pub returned: u64,
+    pub forfeited: u64,
+    pub forfeited_to: Pubkey,
+}
+
+#[event]
+pub struct EvidenceSubmitted {
+    pub dispute: Pubkey,
+    pub evidence: Pubkey,
+    pub submitter: Pubkey,
+    pub index: u8,
+    pub content_hash: [u8; 32],
+    pub uri: String,
+    pub description: String,
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct RulingRecorded {
+    pub dispute: Pubkey,
+    pub arbiter: Pubkey,
+    pub resolution_type: ResolutionType,
+    pub freelancer_bps: u16,
+    pub appeal_deadline: i64,
+}
+
+#[event]
+pub struct RulingAppealed {
+    pub dispute: Pubkey,
+    pub appellant: Pubkey,
+    pub bond: u64,
+    pub first_arbiter: Pubkey,
+}
+
+#[event]
+pub struct AppealBondSettled {
+    pub dispute: Pubkey,
+    pub appellant: Pubkey,
+    pub bond: u64,
+    pub overturned: bool,
+    pub recipient: Pubkey,
+}
+
+#[event]
+pub struct SettlementProposed {
+    pub dispute: Pubkey,
+    pub proposer: Pubkey,
+    pub freelancer_bps: u16,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub dispute: Pubkey,
+    pub job_id: u64,
+    pub resolution_type: ResolutionType,
+    pub freelancer_amount: u64,
+    pub client_amount: u64,
+    pub fee: u64,
 }
 
 pub fn initiate_dispute(
     ctx: Context<InitiateDispute>,
     job_id: u64,
+    milestone_index: Option<u8>,
     reason: String,
-    evidence_ipfs: String,
 ) -> Result<()> {
     let dispute = &mut ctx.accounts.dispute;
-    let job = &mut ctx.accounts.job;
-    let escrow = &mut ctx.accounts.escrow;
-    
-    // Check if job is in progress and has an escrow account
+    let job = &mut ctx.accounts.job_account;
+    let escrow = &mut ctx.accounts.escrow_account;
+    let clock = Clock::get()?;
+
+    // Check if job is in progress and has a freelancer assigned
     require!(job.status == JobStatus::InProgress, DisputeError::InvalidJobStatus);
-    require!(job.selected_freelancer.is_some(), DisputeError::NoFreelancerSelected);
-    
+    let freelancer = job.freelancer.ok_or(DisputeError::NoFreelancerSelected)?;
+
+    // Validate inputs
+    require!(reason.len() <= MAX_REASON_LEN, DisputeError::ReasonTooLong);
+
     // Check if the initiator is either the client or the freelancer
     let initiator = ctx.accounts.initiator.key();
     require!(
-        initiator == &job.client || initiator == job.selected_freelancer.as_ref().unwrap(),
+        initiator == job.client || initiator == freelancer,
         DisputeError::UnauthorizedInitiator
     );
-    
-    // Lock the escrow funds
-    escrow.is_locked = true;
-    
-    // Update job status
-    job.status = JobStatus::Disputed;
-    
+
+    let disputed_amount = match milestone_index {
+        Some(index) => {
+            // Lock only this milestone's tranche; the other milestones stay payable
+            let milestones = job.milestones.as_mut().ok_or(DisputeError::JobHasNoMilestones)?;
+            let tranche = milestone_amount(escrow.amount, milestones, index as usize)?;
+            require!(!escrow.completed_milestones.contains(&index), DisputeError::MilestoneAlreadySettled);
+            require!(escrow.unlocked() >= tranche, DisputeError::TrancheNotFunded);
+
+            let milestone = milestones
+                .get_mut(index as usize)
+                .ok_or(DisputeError::InvalidMilestone)?;
+            milestone.status = MilestoneStatus::Disputed;
+
+            escrow.locked_amount = escrow.locked_amount.checked_add(tranche).ok_or(DisputeError::MathOverflow)?;
+            tranche
+        }
+        None => {
+            // A whole-job dispute cannot overlap open milestone disputes
+            require!(escrow.locked_amount == 0, DisputeError::MilestoneDisputeOpen);
+
+            // Freeze the escrow: release, milestone payments and refunds all require it to be active
+            escrow.status = EscrowStatus::Disputed;
+            job.status = JobStatus::Disputed;
+            escrow.available()
+        }
+    };
+
+    // Lock the filing bond in the escrow, in the escrow's currency. It is kept
+    // out of the escrow totals so it is never paid out as part of a ruling.
+    let bond = ctx.accounts.config.dispute_bond_for(disputed_amount)?;
+    let depositor = EscrowDepositor {
+        escrow,
+        depositor: &ctx.accounts.initiator,
+        vault: &ctx.accounts.vault,
+        system_program: &ctx.accounts.system_program,
+        depositor_token_account: ctx.accounts.initiator_token_account.as_ref(),
+        escrow_token_account: ctx.accounts.escrow_token_account.as_ref(),
+        token_program: ctx.accounts.token_program.as_ref(),
+    };
+    depositor.deposit(bond)?;
+    escrow.dispute_bond = escrow.dispute_bond.checked_add(bond).ok_or(DisputeError::MathOverflow)?;
+    escrow.updated_at = clock.unix_timestamp;
+    job.updated_at = clock.unix_timestamp;
+
     // Set dispute details
+    dispute.job = job.key();
     dispute.job_id = job_id;
-    dispute.escrow = ctx.accounts.escrow.key();
+    dispute.escrow = escrow.key();
     dispute.client = job.client;
-    dispute.freelancer = job.selected_freelancer.unwrap();
-    dispute.initiator = *initiator;
+    dispute.freelancer = freelancer;
+    dispute.initiator = initiator;
+    dispute.milestone_index = milestone_index;
+    dispute.disputed_amount = disputed_amount;
+    dispute.bond = bond;
     dispute.reason = reason;
-    dispute.evidence_ipfs = evidence_ipfs;
+    dispute.response_deadline = clock.unix_timestamp
+        .checked_add(RESPONSE_PERIOD)
+        .ok_or(DisputeError::MathOverflow)?;
+    dispute.responded = false;
+    dispute.evidence_deadline = clock.unix_timestamp
+        .checked_add(EVIDENCE_WINDOW)
+        .ok_or(DisputeError::MathOverflow)?;
+    dispute.client_evidence_count = 0;
+    dispute.freelancer_evidence_count = 0;
     dispute.is_resolved = false;
     dispute.resolution_details = None;
-    dispute.created_at = Clock::get()?.unix_timestamp;
-    
+    dispute.appeal_deadline = None;
+    dispute.appeal = None;
+    dispute.settlement_offer = None;
+    dispute.jury = None;
+    dispute.created_at = clock.unix_timestamp;
+    dispute.bump = ctx.bumps.dispute;
+
+    emit!(DisputeInitiated {
+        dispute: dispute.key(),
+        job: job.key(),
+        job_id,
+        milestone_index,
+        initiator,
+        disputed_amount,
+        bond,
+    });
+
+    Ok(())
+}
+
+pub fn submit_evidence(
+    ctx: Context<SubmitEvidence>,
+    index: u8,
+    content_hash: [u8; 32],
+    uri: String,
+    description: String,
+) -> Result<()> {
+    let dispute = &mut ctx.accounts.dispute;
+    let evidence = &mut ctx.accounts.evidence;
+    let submitter = ctx.accounts.submitter.key();
+    let clock = Clock::get()?;
+
+    // Validate inputs
+    require!(clock.unix_timestamp <= dispute.evidence_deadline, DisputeError::EvidenceWindowClosed);
+    require!(uri.len() <= MAX_EVIDENCE_URI_LEN, DisputeError::EvidenceTooLong);
+    require!(description.len() <= MAX_EVIDENCE_DESCRIPTION_LEN, DisputeError::DescriptionTooLong);
+
+    // Evidence is numbered per party so both sides can submit independently
+    let count = dispute.evidence_count(&submitter)?;
+    require!(index == count, DisputeError::InvalidEvidenceIndex);
+    require!(count < MAX_EVIDENCE_PER_PARTY, DisputeError::TooMuchEvidence);
+
+    if submitter == dispute.client {
+        dispute.client_evidence_count += 1;
+    } else {
+        dispute.freelancer_evidence_count += 1;
+    }
+
+    // Evidence from the respondent only counts as their response if it arrives in time
+    if submitter == dispute.respondent() && clock.unix_timestamp <= dispute.response_deadline {
+        dispute.responded = true;
+    }
+
+    evidence.dispute = dispute.key();
+    evidence.submitter = submitter;
+    evidence.index = index;
+    evidence.content_hash = content_hash;
+    evidence.uri = uri;
+    evidence.description = description;
+    evidence.submitted_at = clock.unix_timestamp;
+    evidence.bump = ctx.bumps.evidence;
+
+    emit!(EvidenceSubmitted {
+        dispute: dispute.key(),
+        evidence: evidence.key(),
+        submitter,
+        index,
+        content_hash,
+        uri: evidence.uri.clone(),
+        description: evidence.description.clone(),
+        submitted_at: evidence.submitted_at,
+    });
+
     Ok(())
 }
 
 pub fn resolve_dispute(
     ctx: Context<ResolveDispute>,
-    job_id: u64,
-    release_to_freelancer: bool,
-    split_ratio: u64,
+    resolution_type: ResolutionType,
+    split_bps: u16,
+    resolution_note: String,
 ) -> Result<()> {
-    let dispute = &mut ctx.accounts.dispute;
-    let job = &mut ctx.accounts.job;
-    let escrow = &mut ctx.accounts.escrow;
-    
-    // Check that dispute isn't already resolved
-    require!(!dispute.is_resolved, DisputeError::AlreadyResolved);
-    // Validate that the split ratio is within range
-    if !release_to_freelancer && split_ratio > 0 {
-        require!(split_ratio <= 100, DisputeError::InvalidSplitRatio);
+    require!(resolution_note.len() <= MAX_RESOLUTION_NOTE_LEN, DisputeError::NoteTooLong);
+
+    let freelancer_bps = resolution_type.freelancer_bps(split_bps)?;
+    let arbiter = ctx.accounts.arbiter.key();
+    let clock = Clock::get()?;
+
+    let details = ResolutionDetails {
+        resolved_by: arbiter,
+        resolution_type,
+        split_bps: if resolution_type == ResolutionType::Split { Some(split_bps) } else { None },
+        resolution_note,
+        resolved_at: clock.unix_timestamp,
+    };
+
+    // A ruling on appeal is final and pays out straight away
+    if let Some(appeal) = ctx.accounts.payout.dispute.appeal.clone() {
+        require!(arbiter != appeal.first_arbiter, DisputeError::SameArbiter);
+
+        ctx.accounts.payout.settle(details, clock.unix_timestamp)?;
+
+        // The bond is refunded only if the new ruling moved in the appellant's favour
+        let payout = &ctx.accounts.payout;
+        let (overturned, appellant_account) = if appeal.appellant == payout.dispute.client {
+            (freelancer_bps < appeal.first_freelancer_bps, &payout.client)
+        } else {
+            (freelancer_bps > appeal.first_freelancer_bps, &payout.freelancer)
+        };
+        let recipient = if overturned { appellant_account } else { &payout.treasury };
+
+        **payout.dispute.to_account_info().try_borrow_mut_lamports()? -= appeal.bond;
+        **recipient.try_borrow_mut_lamports()? += appeal.bond;
+
+        emit!(AppealBondSettled {
+            dispute: payout.dispute.key(),
+            appellant: appeal.appellant,
+            bond: appeal.bond,
+            overturned,
+            recipient: recipient.key(),
+        });
+
+        return Ok(());
     }
-    
-    // Determine the resolution type
-    let resolution_type = if release_to_freelancer {
-        ResolutionType::ReleaseToFreelancer
-    } else if split_ratio > 0 {
-        ResolutionType::Split
+
+    let appeal_window = ctx.accounts.payout.config.appeal_window;
+    if appeal_window > 0 {
+        // Record the ruling; the escrow only pays out once the appeal window closes
+        let appeal_deadline = clock.unix_timestamp
+            .checked_add(appeal_window)
+            .ok_or(DisputeError::MathOverflow)?;
+        let dispute = &mut ctx.accounts.payout.dispute;
+        dispute.resolution_details = Some(details);
+        dispute.appeal_deadline = Some(appeal_deadline);
+
+        emit!(RulingRecorded {
+            dispute: dispute.key(),
+            arbiter,
+            resolution_type,
+            freelancer_bps,
+            appeal_deadline,
+        });
+
+        return Ok(());
+    }
+
+    // Appeals are disabled: pay out the remaining escrow according to the ruling
+    ctx.accounts.payout.settle(details, clock.unix_timestamp)?;
+
+    Ok(())
+}
+
+pub fn appeal_ruling(ctx: Context<AppealRuling>) -> Result<()> {
+    let dispute = &mut ctx.accounts.dispute;
+    let appellant = ctx.accounts.appellant.key();
+    let clock = Clock::get()?;
+
+    let appeal_deadline = dispute.appeal_deadline.ok_or(DisputeError::NoRulingPending)?;
+    require!(clock.unix_timestamp <= appeal_deadline, DisputeError::AppealWindowClosed);
+    let ruling = dispute.resolution_details.clone().ok_or(DisputeError::NoRulingPending)?;
+    let freelancer_bps = ruling.freelancer_bps()?;
+
+    // Only a party who lost something under the ruling can appeal it
+    let lost = if appellant == dispute.client {
+        freelancer_bps > 0
+    } else if appellant == dispute.freelancer {
+        freelancer_bps < TOTAL_SPLIT_BPS
     } else {
-        ResolutionType::RefundToClient
+        return err!(DisputeError::InvalidParty);
     };
-    
-    // Handle funds transfer based on resolution
-    let amount = escrow.amount;
-    let platform_fee = escrow.platform_fee;
-    
-    match resolution_type {
-        ResolutionType::ReleaseToFreelancer => {
-            // Transfer all funds to freelancer
-            **escrow.to_account_info().try_borrow_mut_lamports()? -= amount;
-            **ctx.accounts.freelancer.try_borrow_mut_lamports()? += amount;
-            
-            // Transfer platform fee
-            **escrow.to_account_info().try_borrow_mut_lamports()? -= platform_fee;
-            **ctx.accounts.platform_fee_account.try_borrow_mut_lamports()? += platform_fee;
-        },
-        ResolutionType::RefundToClient => {
-            // Refund all funds to client
-            **escrow.to_account_info().try_borrow_mut_lamports()? -= amount + platform_fee;
-            **ctx.accounts.client.try_borrow_mut_lamports()? += amount + platform_fee;
-        },
-        ResolutionType::Split => {
-            // Calculate split amounts
-            let freelancer_amount = amount * split_ratio / 100;
-            let client_amount = amount - freelancer_amount;
-            
-            // Transfer to freelancer
-            **escrow.to_account_info().try_borrow_mut_lamports()? -= freelancer_amount;
-            **ctx.accounts.freelancer.try_borrow_mut_lamports()? += freelancer_amount;
-            
-            // Transfer to client
-            **escrow.to_account_info().try_borrow_mut_lamports()? -= client_amount;
-            **ctx.accounts.client.try_borrow_mut_lamports()? += client_amount;
-            
-            // Handle platform fee based on split ratio
-            let freelancer_fee = platform_fee * split_ratio / 100;
-            let client_fee = platform_fee - freelancer_fee;
-            
-            // Transfer platform fee from escrow
-            **escrow.to_account_info().try_borrow_mut_lamports()? -= platform_fee;
-            **ctx.accounts.platform_fee_account.try_borrow_mut_lamports()? += platform_fee;
-        }
+    require!(lost, DisputeError::NothingToAppeal);
+
+    // Lock the appeal bond in the dispute account
+    let bond = ctx.accounts.config.appeal_bond;
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.appellant.to_account_info(),
+                to: dispute.to_account_info(),
+            },
+        ),
+        bond,
+    )?;
+
+    dispute.appeal = Some(Appeal {
+        appellant,
+        bond,
+        first_arbiter: ruling.resolved_by,
+        first_freelancer_bps: freelancer_bps,
+        appealed_at: clock.unix_timestamp,
+    });
+    dispute.resolution_details = None;
+    dispute.appeal_deadline = None;
+
+    emit!(RulingAppealed {
+        dispute: dispute.key(),
+        appellant,
+        bond,
+        first_arbiter: ruling.resolved_by,
+    });
+
+    Ok(())
+}
+
+pub fn execute_ruling(ctx: Context<ExecuteRuling>) -> Result<()> {
+    let clock = Clock::get()?;
+    let dispute = &ctx.accounts.payout.dispute;
+
+    let appeal_deadline = dispute.appeal_deadline.ok_or(DisputeError::NoRulingPending)?;
+    require!(clock.unix_timestamp > appeal_deadline, DisputeError::AppealWindowOpen);
+    let details = dispute.resolution_details.clone().ok_or(DisputeError::NoRulingPending)?;
+
+    ctx.accounts.payout.settle(details, clock.unix_timestamp)?;
+
+    Ok(())
+}
+
+// Offers to end the dispute on a split; replaces any earlier offer
+pub fn propose_settlement(ctx: Context<ProposeSettlement>, freelancer_bps: u16) -> Result<()> {
+    let dispute = &mut ctx.accounts.dispute;
+    let proposer = ctx.accounts.proposer.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(freelancer_bps <= TOTAL_SPLIT_BPS, DisputeError::InvalidSplitRatio);
+
+    // A timely offer from the respondent answers the dispute, just like evidence
+    if proposer == dispute.respondent() && now <= dispute.response_deadline {
+        dispute.responded = true;
     }
-    
-    // Mark dispute as resolved
-    dispute.is_resolved = true;
-    dispute.resolution_details = Some(ResolutionDetails {
-        resolved_by: *ctx.accounts.admin.key,
-        resolution_type,
-        split_ratio: if resolution_type == ResolutionType::Split { Some(split_ratio) } else { None },
-        resolution_note: String::from("Dispute resolved by admin"),
-        resolved_at: Clock::get()?.unix_timestamp,
+
+    dispute.settlement_offer = Some(SplitOffer {
+        proposer,
+        freelancer_bps,
+        proposed_at: now,
+    });
+
+    emit!(SettlementProposed {
+        dispute: dispute.key(),
+        proposer,
+        freelancer_bps,
     });
-    
-    // Unlock escrow
-    escrow.is_locked = false;
-    
-    // Update job status
-    job.status = JobStatus::Completed;
-    
+
+    Ok(())
+}
+
+pub fn accept_settlement(ctx: Context<AcceptSettlement>, freelancer_bps: u16) -> Result<()> {
+    let clock = Clock::get()?;
+    let acceptor = ctx.accounts.acceptor.key();
+    let dispute = &ctx.accounts.payout.dispute;
+
+    SplitOffer::check_acceptance(
+        dispute.settlement_offer.as_ref(),
+        acceptor,
+        freelancer_bps,
+        DisputeError::NoSettlementOffer,
+        DisputeError::OwnSettlementOffer,
+        DisputeError::SettlementOfferChanged,
+    )?;
+    let appeal = dispute.appeal.clone();
+
+    ctx.accounts.payout.settle(
+        ResolutionDetails {
+            resolved_by: acceptor,
+            resolution_type: ResolutionType::Settled,
+            split_bps: Some(freelancer_bps),
+            resolution_note: String::from("Settled by agreement between the parties"),
+            resolved_at: clock.unix_timestamp,
+        },
+        clock.unix_timestamp,
+    )?;
+
+    // A settlement ends any appeal, so the appellant gets their bond back
+    if let Some(appeal) = appeal {
+        let payout = &ctx.accounts.payout;
+        let appellant = if appeal.appellant == payout.dispute.client {
+            &payout.client
+        } else {
+            &payout.freelancer
+        };
+
+        **payout.dispute.to_account_info().try_borrow_mut_lamports()? -= appeal.bond;
+        **appellant.try_borrow_mut_lamports()? += appeal.bond;
+
+        emit!(AppealBondSettled {
+            dispute: payout.dispute.key(),
+            appellant: appeal.appellant,
+            bond: appeal.bond,
+            overturned: false,
+            recipient: appellant.key(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn claim_default_judgment(ctx: Context<ClaimDefaultJudgment>) -> Result<()> {
+    let clock = Clock::get()?;
+    let dispute = &ctx.accounts.payout.dispute;
+
+    require!(clock.unix_timestamp > dispute.response_deadline, DisputeError::ResponsePeriodActive);
+
+    // Decide fully in the initiator's favour
+    let resolution_type = if dispute.initiator == dispute.client {
+        ResolutionType::RefundToClient
+    } else {
+        ResolutionType::ReleaseToFreelancer
+    };
+
+    ctx.accounts.payout.settle(
+        ResolutionDetails {
+            resolved_by: ctx.accounts.initiator.key(),
+            resolution_type,
+            split_bps: None,
+            resolution_note: String::from("Default judgment: respondent did not respond"),
+            resolved_at: clock.unix_timestamp,
+        },
+        clock.unix_timestamp,
+    )?;
+
     Ok(())
 }
 
@@ -222,6 +1102,74 @@ pub enum DisputeError {
     UnauthorizedInitiator,
     #[msg("Dispute already resolved")]
     AlreadyResolved,
-    #[msg("Split ratio must be between 0 and 100")]
+    #[msg("Split must be between 0 and 10000 basis points")]
     InvalidSplitRatio,
-} 
\ No newline at end of file
+    #[msg("Escrow does not belong to this job")]
+    InvalidEscrow,
+    #[msg("Escrow is not active")]
+    EscrowNotActive,
+    #[msg("Escrow is not under dispute")]
+    EscrowNotDisputed,
+    #[msg("Account is not a party to this dispute")]
+    InvalidParty,
+    #[msg("Treasury does not match the marketplace config")]
+    InvalidTreasury,
+    #[msg("Only a registered arbiter can resolve disputes")]
+    UnauthorizedResolver,
+    #[msg("Arbiter cannot be a party to the job")]
+    ArbiterIsParty,
+    #[msg("Dispute is being decided by a jury")]
+    JuryAssigned,
+    #[msg("Reason is too long")]
+    ReasonTooLong,
+    #[msg("Evidence URI is too long")]
+    EvidenceTooLong,
+    #[msg("Evidence description is too long")]
+    DescriptionTooLong,
+    #[msg("Evidence window has closed")]
+    EvidenceWindowClosed,
+    #[msg("Maximum evidence per party reached")]
+    TooMuchEvidence,
+    #[msg("Evidence index must be the submitter's next index")]
+    InvalidEvidenceIndex,
+    #[msg("Only the dispute initiator can claim a default judgment")]
+    NotInitiator,
+    #[msg("Respondent has answered the dispute")]
+    RespondentAnswered,
+    #[msg("Respondent still has time to answer")]
+    ResponsePeriodActive,
+    #[msg("A ruling is waiting out its appeal window")]
+    RulingPending,
+    #[msg("No ruling is pending")]
+    NoRulingPending,
+    #[msg("Appeal window has closed")]
+    AppealWindowClosed,
+    #[msg("Appeal window is still open")]
+    AppealWindowOpen,
+    #[msg("Ruling has already been appealed")]
+    AlreadyAppealed,
+    #[msg("Ruling gave this party everything they could get")]
+    NothingToAppeal,
+    #[msg("Appeal must be decided by a different arbiter")]
+    SameArbiter,
+    #[msg("No settlement offer to accept")]
+    NoSettlementOffer,
+    #[msg("Cannot accept your own settlement offer")]
+    OwnSettlementOffer,
+    #[msg("Settlement offer does not match the accepted split")]
+    SettlementOfferChanged,
+    #[msg("Job does not have milestones")]
+    JobHasNoMilestones,
+    #[msg("Invalid milestone index")]
+    InvalidMilestone,
+    #[msg("Milestone has already been paid out")]
+    MilestoneAlreadySettled,
+    #[msg("A milestone dispute is still open on this job")]
+    MilestoneDisputeOpen,
+    #[msg("Escrow does not hold the disputed milestone's funds")]
+    TrancheNotFunded,
+    #[msg("Resolution note is too long")]
+    NoteTooLong,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
\ No newline at end of file
@@ -1,7 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer, CloseAccount};
 use anchor_spl::associated_token::AssociatedToken;
-use crate::job::{Job, JobStatus, Milestone};
+use crate::config::Config;
+use crate::job::{Bid, Job, JobStatus, Milestone, MilestoneStatus, TOTAL_MILESTONE_BPS};
+
+// How long after the job deadline a client may refund an accepted contract on their own
+pub const REFUND_GRACE_PERIOD: i64 = 7 * 24 * 60 * 60; // 7 days
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub enum EscrowStatus {
@@ -16,12 +21,54 @@ pub struct Escrow {
     pub job: Pubkey,
     pub client: Pubkey,
     pub freelancer: Pubkey,
-    pub amount: u64,
+    pub amount: u64, // Contract value; grows when top-ups exceed it
+    pub total_deposited: u64,
+    pub total_released: u64, // Everything paid out of the escrow, to either party
     pub status: EscrowStatus,
     pub token_mint: Option<Pubkey>, // None means SOL, Some(pubkey) means SPL token
     pub created_at: i64,
     pub updated_at: i64,
     pub completed_milestones: Vec<u8>, // Indices of completed milestones
+    pub dispute_bond: u64, // Bonds locked by dispute initiators; held alongside, not part of, the escrowed funds
+    pub locked_amount: u64, // Share of the escrow frozen by open milestone disputes
+    pub cancellation_offer: Option<SplitOffer>,
+    pub bump: u8,
+}
+
+// A split of the remaining escrow one party has offered the other, to cancel the
+// contract or to settle a dispute
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SplitOffer {
+    pub proposer: Pubkey,
+    pub freelancer_bps: u16, // Share of the remaining escrow to the freelancer
+    pub proposed_at: i64,
+}
+
+impl SplitOffer {
+    pub const SPACE: usize = 32 + // proposer
+        2 + // freelancer_bps
+        8; // proposed_at
+    
+    // Checks the other party is taking up the offer on the split they expect, so an
+    // offer swapped out just before acceptance is rejected. Callers pass their own
+    // errors for a missing offer, their own offer and a changed split.
+    pub fn check_acceptance<E: Into<anchor_lang::error::Error>>(
+        offer: Option<&SplitOffer>,
+        acceptor: Pubkey,
+        freelancer_bps: u16,
+        no_offer: E,
+        own_offer: E,
+        offer_changed: E,
+    ) -> Result<()> {
+        let offer = offer.ok_or_else(|| no_offer.into())?;
+        if offer.proposer == acceptor {
+            return Err(own_offer.into());
+        }
+        if offer.freelancer_bps != freelancer_bps {
+            return Err(offer_changed.into());
+        }
+        Ok(())
+    }
 }
 
 impl Escrow {
@@ -30,11 +77,56 @@ impl Escrow {
         32 + // client
         32 + // freelancer
         8 + // amount
+        8 + // total_deposited
+        8 + // total_released
         1 + // status
         1 + 32 + // token_mint (Option<Pubkey>)
         8 + // created_at
         8 + // updated_at
-        4 + 50; // completed_milestones (Vec<u8> - max 50 milestones)
+        4 + 50 + // completed_milestones (Vec<u8> - max 50 milestones)
+        8 + // dispute_bond
+        8 + // locked_amount
+        1 + SplitOffer::SPACE + // cancellation_offer
+        1; // bump
+    
+    // Funds deposited that have not been paid out yet
+    pub fn available(&self) -> u64 {
+        self.total_deposited.saturating_sub(self.total_released)
+    }
+    
+    // Funds that are not frozen by an open milestone dispute
+    pub fn unlocked(&self) -> u64 {
+        self.available().saturating_sub(self.locked_amount)
+    }
+    
+    // Records a payout, failing if the unlocked funds do not cover it
+    pub fn record_payout(&mut self, amount: u64) -> Result<()> {
+        if self.unlocked() < amount {
+            return err!(ErrorCode::EscrowUnderfunded);
+        }
+        
+        self.total_released = self.total_released.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+    
+    // Seeds the escrow PDA signs with when moving tokens out of its ATA
+    pub fn signer_seeds(&self) -> [&[u8]; 3] {
+        [b"escrow", self.job.as_ref(), std::slice::from_ref(&self.bump)]
+    }
+}
+
+// Holds the lamports of SOL escrows. The vault keeps its own rent-exempt
+// minimum; only lamports above it belong to the escrow.
+#[account]
+pub struct EscrowVault {
+    pub escrow: Pubkey,
+    pub bump: u8,
+}
+
+impl EscrowVault {
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // escrow
+        1; // bump
 }
 
 #[event]
@@ -46,12 +138,22 @@ pub struct EscrowCreated {
     pub is_token: bool,
 }
 
+#[event]
+pub struct EscrowFunded {
+    pub job: Pubkey,
+    pub escrow: Pubkey,
+    pub client: Pubkey,
+    pub amount: u64,
+    pub total_deposited: u64,
+}
+
 #[event]
 pub struct EscrowReleased {
     pub job: Pubkey,
     pub client: Pubkey,
     pub freelancer: Pubkey,
     pub amount: u64,
+    pub fee: u64,
 }
 
 #[event]
@@ -60,6 +162,15 @@ pub struct MilestoneCompleted {
     pub escrow: Pubkey,
     pub milestone_index: u8,
     pub amount: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct ClaimedAfterTimeout {
+    pub job: Pubkey,
+    pub caller: Pubkey,
+    pub milestone_index: Option<u8>,
+    pub amount: u64,
 }
 
 #[event]
@@ -69,27 +180,69 @@ pub struct EscrowRefunded {
     pub amount: u64,
 }
 
+#[event]
+pub struct CancellationProposed {
+    pub job: Pubkey,
+    pub proposer: Pubkey,
+    pub freelancer_bps: u16,
+}
+
+#[event]
+pub struct ContractCancelled {
+    pub job: Pubkey,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub freelancer_amount: u64,
+    pub client_amount: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct EscrowClosed {
+    pub job: Pubkey,
+    pub escrow: Pubkey,
+    pub client: Pubkey,
+}
+
 #[derive(Accounts)]
-#[instruction(amount: u64, token_mint: Option<Pubkey>)]
+#[instruction(amount: u64, token_mint: Option<Pubkey>, initial_deposit: u64)]
 pub struct CreateEscrow<'info> {
     #[account(mut)]
     pub client: Signer<'info>,
     
     #[account(
         mut,
+        seeds = [b"job", client.key().as_ref(), &job_account.id.to_le_bytes()],
+        bump = job_account.bump,
         constraint = job_account.client == client.key() @ ErrorCode::UnauthorizedAccess,
         constraint = job_account.status == JobStatus::InProgress @ ErrorCode::JobNotInProgress,
         constraint = job_account.freelancer.is_some() @ ErrorCode::FreelancerNotAssigned,
     )]
     pub job_account: Account<'info, Job>,
     
+    #[account(
+        constraint = job_account.accepted_bid == Some(accepted_bid.key()) @ ErrorCode::InvalidBid,
+    )]
+    pub accepted_bid: Account<'info, Bid>,
+    
     #[account(
         init,
         payer = client,
         space = Escrow::SPACE,
+        seeds = [b"escrow", job_account.key().as_ref()],
+        bump,
     )]
     pub escrow_account: Account<'info, Escrow>,
     
+    #[account(
+        init,
+        payer = client,
+        space = EscrowVault::SPACE,
+        seeds = [b"vault", escrow_account.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, EscrowVault>,
+    
     pub system_program: Program<'info, System>,
     
     // Optional token accounts
@@ -117,6 +270,56 @@ pub struct CreateEscrow<'info> {
     pub rent: Option<Sysvar<'info, Rent>>,
 }
 
+#[derive(Accounts)]
+#[instruction(job_id: u64, amount: u64)]
+pub struct FundEscrow<'info> {
+    #[account(mut)]
+    pub client: Signer<'info>,
+    
+    #[account(
+        seeds = [b"job", job_account.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job_account.bump,
+        constraint = job_account.client == client.key() @ ErrorCode::UnauthorizedAccess,
+        constraint = job_account.escrow == Some(escrow_account.key()) @ ErrorCode::InvalidEscrow,
+    )]
+    pub job_account: Account<'info, Job>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", job_account.key().as_ref()],
+        bump = escrow_account.bump,
+        constraint = escrow_account.status != EscrowStatus::Disputed @ ErrorCode::EscrowDisputed,
+        constraint = escrow_account.status == EscrowStatus::Active @ ErrorCode::EscrowNotActive,
+    )]
+    pub escrow_account: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_account.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, EscrowVault>,
+    
+    pub system_program: Program<'info, System>,
+    
+    // Optional token accounts
+    pub token_program: Option<Program<'info, Token>>,
+    
+    #[account(
+        mut,
+        token::mint = escrow_account.token_mint.unwrap(),
+        token::authority = client,
+    )]
+    pub client_token_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = escrow_account,
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+}
+
 #[derive(Accounts)]
 #[instruction(job_id: u64)]
 pub struct ReleaseEscrow<'info> {
@@ -125,6 +328,8 @@ pub struct ReleaseEscrow<'info> {
     
     #[account(
         mut,
+        seeds = [b"job", job_account.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job_account.bump,
         constraint = job_account.client == client.key() @ ErrorCode::UnauthorizedAccess,
         constraint = job_account.escrow.is_some() @ ErrorCode::EscrowNotCreated,
         constraint = job_account.escrow.unwrap() == escrow_account.key() @ ErrorCode::InvalidEscrow,
@@ -133,11 +338,22 @@ pub struct ReleaseEscrow<'info> {
     
     #[account(
         mut,
+        seeds = [b"escrow", job_account.key().as_ref()],
+        bump = escrow_account.bump,
         constraint = escrow_account.job == job_account.key() @ ErrorCode::InvalidEscrow,
+        constraint = escrow_account.status != EscrowStatus::Disputed @ ErrorCode::EscrowDisputed,
+        constraint = escrow_account.locked_amount == 0 @ ErrorCode::MilestoneDisputed,
         constraint = escrow_account.status == EscrowStatus::Active @ ErrorCode::EscrowNotActive,
     )]
     pub escrow_account: Account<'info, Escrow>,
     
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_account.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, EscrowVault>,
+    
     #[account(
         mut,
         constraint = freelancer.key() == job_account.freelancer.unwrap() @ ErrorCode::InvalidFreelancer,
@@ -152,7 +368,8 @@ pub struct ReleaseEscrow<'info> {
     
     #[account(
         mut,
-        token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = escrow_account,
     )]
     pub escrow_token_account: Option<Account<'info, TokenAccount>>,
     
@@ -162,6 +379,23 @@ pub struct ReleaseEscrow<'info> {
         token::authority = freelancer,
     )]
     pub freelancer_token_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        address = config.treasury @ ErrorCode::InvalidTreasury,
+    )]
+    /// CHECK: Platform treasury wallet, receives SOL fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = config.treasury,
+    )]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
 }
 
 #[derive(Accounts)]
@@ -172,6 +406,8 @@ pub struct MilestonePayment<'info> {
     
     #[account(
         mut,
+        seeds = [b"job", job_account.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job_account.bump,
         constraint = job_account.client == client.key() @ ErrorCode::UnauthorizedAccess,
         constraint = job_account.escrow.is_some() @ ErrorCode::EscrowNotCreated,
         constraint = job_account.escrow.unwrap() == escrow_account.key() @ ErrorCode::InvalidEscrow,
@@ -181,11 +417,21 @@ pub struct MilestonePayment<'info> {
     
     #[account(
         mut,
+        seeds = [b"escrow", job_account.key().as_ref()],
+        bump = escrow_account.bump,
         constraint = escrow_account.job == job_account.key() @ ErrorCode::InvalidEscrow,
+        constraint = escrow_account.status != EscrowStatus::Disputed @ ErrorCode::EscrowDisputed,
         constraint = escrow_account.status == EscrowStatus::Active @ ErrorCode::EscrowNotActive,
     )]
     pub escrow_account: Account<'info, Escrow>,
     
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_account.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, EscrowVault>,
+    
     #[account(
         mut,
         constraint = freelancer.key() == job_account.freelancer.unwrap() @ ErrorCode::InvalidFreelancer,
@@ -198,9 +444,86 @@ pub struct MilestonePayment<'info> {
     // Optional token accounts
     pub token_program: Option<Program<'info, Token>>,
     
+    #[account(
+        mut,
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = escrow_account,
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+    
     #[account(
         mut,
         token::mint = escrow_account.token_mint.unwrap(),
+        token::authority = freelancer,
+    )]
+    pub freelancer_token_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        address = config.treasury @ ErrorCode::InvalidTreasury,
+    )]
+    /// CHECK: Platform treasury wallet, receives SOL fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = config.treasury,
+    )]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+#[instruction(job_id: u64, milestone_index: Option<u8>)]
+pub struct ClaimAfterTimeout<'info> {
+    // Anyone can claim on the freelancer's behalf once the review period lapses
+    pub caller: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"job", job_account.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job_account.bump,
+        constraint = job_account.escrow.is_some() @ ErrorCode::EscrowNotCreated,
+        constraint = job_account.escrow.unwrap() == escrow_account.key() @ ErrorCode::InvalidEscrow,
+    )]
+    pub job_account: Account<'info, Job>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", job_account.key().as_ref()],
+        bump = escrow_account.bump,
+        constraint = escrow_account.job == job_account.key() @ ErrorCode::InvalidEscrow,
+        constraint = escrow_account.status != EscrowStatus::Disputed @ ErrorCode::EscrowDisputed,
+        constraint = escrow_account.status == EscrowStatus::Active @ ErrorCode::EscrowNotActive,
+    )]
+    pub escrow_account: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_account.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, EscrowVault>,
+    
+    #[account(
+        mut,
+        constraint = freelancer.key() == job_account.freelancer.unwrap() @ ErrorCode::InvalidFreelancer,
+    )]
+    /// CHECK: This account is not written to, just receives funds
+    pub freelancer: AccountInfo<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    // Optional token accounts
+    pub token_program: Option<Program<'info, Token>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = escrow_account,
     )]
     pub escrow_token_account: Option<Account<'info, TokenAccount>>,
     
@@ -210,6 +533,23 @@ pub struct MilestonePayment<'info> {
         token::authority = freelancer,
     )]
     pub freelancer_token_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        address = config.treasury @ ErrorCode::InvalidTreasury,
+    )]
+    /// CHECK: Platform treasury wallet, receives SOL fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = config.treasury,
+    )]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
 }
 
 #[derive(Accounts)]
@@ -220,6 +560,8 @@ pub struct RefundEscrow<'info> {
     
     #[account(
         mut,
+        seeds = [b"job", job_account.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job_account.bump,
         constraint = job_account.client == client.key() @ ErrorCode::UnauthorizedAccess,
         constraint = job_account.escrow.is_some() @ ErrorCode::EscrowNotCreated,
         constraint = job_account.escrow.unwrap() == escrow_account.key() @ ErrorCode::InvalidEscrow,
@@ -228,11 +570,22 @@ pub struct RefundEscrow<'info> {
     
     #[account(
         mut,
+        seeds = [b"escrow", job_account.key().as_ref()],
+        bump = escrow_account.bump,
         constraint = escrow_account.job == job_account.key() @ ErrorCode::InvalidEscrow,
+        constraint = escrow_account.status != EscrowStatus::Disputed @ ErrorCode::EscrowDisputed,
+        constraint = escrow_account.locked_amount == 0 @ ErrorCode::MilestoneDisputed,
         constraint = escrow_account.status == EscrowStatus::Active @ ErrorCode::EscrowNotActive,
     )]
     pub escrow_account: Account<'info, Escrow>,
     
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_account.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, EscrowVault>,
+    
     pub system_program: Program<'info, System>,
     
     // Optional token accounts
@@ -240,7 +593,8 @@ pub struct RefundEscrow<'info> {
     
     #[account(
         mut,
-        token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = escrow_account,
     )]
     pub escrow_token_account: Option<Account<'info, TokenAccount>>,
     
@@ -252,162 +606,336 @@ pub struct RefundEscrow<'info> {
     pub client_token_account: Option<Account<'info, TokenAccount>>,
 }
 
-pub fn create_escrow(
-    ctx: Context<CreateEscrow>,
-    amount: u64,
-    token_mint: Option<Pubkey>,
-) -> Result<()> {
-    let job = &mut ctx.accounts.job_account;
-    let escrow = &mut ctx.accounts.escrow_account;
-    let client = &ctx.accounts.client;
-    let clock = Clock::get()?;
-    
-    // Validate inputs
-    if amount == 0 {
-        return err!(ErrorCode::InvalidAmount);
-    }
-    
-    // Initialize escrow
-    escrow.job = job.key();
-    escrow.client = client.key();
-    escrow.freelancer = job.freelancer.unwrap();
-    escrow.amount = amount;
-    escrow.status = EscrowStatus::Active;
-    escrow.token_mint = token_mint;
-    escrow.created_at = clock.unix_timestamp;
-    escrow.updated_at = clock.unix_timestamp;
-    escrow.completed_milestones = Vec::new();
-    
-    // Update job with escrow reference
-    job.escrow = Some(escrow.key());
-    job.updated_at = clock.unix_timestamp;
-    
-    // Transfer funds from client to escrow
-    if let Some(token_mint) = token_mint {
-        // Transfer SPL tokens
-        let client_token_account = ctx.accounts.client_token_account.as_ref().unwrap();
-        let escrow_token_account = ctx.accounts.escrow_token_account.as_ref().unwrap();
-        let token_program = ctx.accounts.token_program.as_ref().unwrap();
-        
-        let transfer_instruction = Transfer {
-            from: client_token_account.to_account_info(),
-            to: escrow_token_account.to_account_info(),
-            authority: client.to_account_info(),
-        };
-        
-        token::transfer(
-            CpiContext::new(
-                token_program.to_account_info(),
-                transfer_instruction,
-            ),
-            amount,
-        )?;
-    } else {
-        // Transfer SOL
-        let rent = Rent::get()?;
-        let transfer_amount = amount;
-        
-        if client.lamports() < transfer_amount {
-            return err!(ErrorCode::InsufficientFunds);
-        }
-        
-        // Transfer from client to escrow account
-        **escrow.to_account_info().try_borrow_mut_lamports()? += transfer_amount;
-        **client.to_account_info().try_borrow_mut_lamports()? -= transfer_amount;
-    }
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct ProposeCancellation<'info> {
+    pub proposer: Signer<'info>,
     
-    // Emit event
-    emit!(EscrowCreated {
-        job: job.key(),
-        client: client.key(),
-        freelancer: escrow.freelancer,
-        amount,
-        is_token: token_mint.is_some(),
-    });
+    #[account(
+        seeds = [b"job", job_account.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job_account.bump,
+        constraint = job_account.escrow == Some(escrow_account.key()) @ ErrorCode::InvalidEscrow,
+        constraint = job_account.status == JobStatus::InProgress @ ErrorCode::JobNotInProgress,
+    )]
+    pub job_account: Account<'info, Job>,
     
-    Ok(())
+    #[account(
+        mut,
+        seeds = [b"escrow", job_account.key().as_ref()],
+        bump = escrow_account.bump,
+        constraint = proposer.key() == escrow_account.client
+            || proposer.key() == escrow_account.freelancer @ ErrorCode::UnauthorizedAccess,
+        constraint = escrow_account.status != EscrowStatus::Disputed @ ErrorCode::EscrowDisputed,
+        constraint = escrow_account.locked_amount == 0 @ ErrorCode::MilestoneDisputed,
+        constraint = escrow_account.status == EscrowStatus::Active @ ErrorCode::EscrowNotActive,
+    )]
+    pub escrow_account: Account<'info, Escrow>,
 }
 
-pub fn release_escrow(ctx: Context<ReleaseEscrow>, job_id: u64) -> Result<()> {
-    let job = &mut ctx.accounts.job_account;
-    let escrow = &mut ctx.accounts.escrow_account;
-    let freelancer = &ctx.accounts.freelancer;
-    let clock = Clock::get()?;
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct AcceptCancellation<'info> {
+    pub acceptor: Signer<'info>,
     
-    // Calculate amount to release (full escrow amount if no milestones or all milestones have been paid)
-    let mut amount_to_release = escrow.amount;
+    #[account(
+        mut,
+        seeds = [b"job", job_account.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job_account.bump,
+        constraint = job_account.escrow == Some(escrow_account.key()) @ ErrorCode::InvalidEscrow,
+        constraint = job_account.status == JobStatus::InProgress @ ErrorCode::JobNotInProgress,
+    )]
+    pub job_account: Account<'info, Job>,
     
-    if job.has_milestones {
-        // Check if there are any milestones that haven't been paid
-        let milestones = job.milestones.as_ref().unwrap();
-        let total_milestone_count = milestones.len();
-        let completed_milestone_count = escrow.completed_milestones.len();
-        
-        if completed_milestone_count < total_milestone_count {
-            // Calculate remaining amount
-            let paid_amount: u64 = escrow.completed_milestones.iter()
-                .map(|&idx| {
-                    let milestone = &milestones[idx as usize];
-                    (escrow.amount * milestone.amount as u64) / 10000
-                })
-                .sum();
-            
-            amount_to_release = escrow.amount.saturating_sub(paid_amount);
-        }
+    #[account(
+        mut,
+        seeds = [b"escrow", job_account.key().as_ref()],
+        bump = escrow_account.bump,
+        constraint = acceptor.key() == escrow_account.client
+            || acceptor.key() == escrow_account.freelancer @ ErrorCode::UnauthorizedAccess,
+        constraint = escrow_account.status != EscrowStatus::Disputed @ ErrorCode::EscrowDisputed,
+        constraint = escrow_account.locked_amount == 0 @ ErrorCode::MilestoneDisputed,
+        constraint = escrow_account.status == EscrowStatus::Active @ ErrorCode::EscrowNotActive,
+    )]
+    pub escrow_account: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_account.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, EscrowVault>,
+    
+    #[account(
+        mut,
+        address = escrow_account.client @ ErrorCode::UnauthorizedAccess,
+    )]
+    /// CHECK: This account is not written to, just receives funds
+    pub client: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = escrow_account.freelancer @ ErrorCode::InvalidFreelancer,
+    )]
+    /// CHECK: This account is not written to, just receives funds
+    pub freelancer: AccountInfo<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    // Optional token accounts
+    pub token_program: Option<Program<'info, Token>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = escrow_account,
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        token::mint = escrow_account.token_mint.unwrap(),
+        token::authority = client,
+    )]
+    pub client_token_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        token::mint = escrow_account.token_mint.unwrap(),
+        token::authority = freelancer,
+    )]
+    pub freelancer_token_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        address = config.treasury @ ErrorCode::InvalidTreasury,
+    )]
+    /// CHECK: Platform treasury wallet, receives SOL fees
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = config.treasury,
+    )]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEscrow<'info> {
+    #[account(mut)]
+    pub client: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = job_account.client == client.key() @ ErrorCode::UnauthorizedAccess,
+        constraint = job_account.escrow == Some(escrow_account.key()) @ ErrorCode::InvalidEscrow,
+        constraint = job_account.status != JobStatus::Disputed @ ErrorCode::EscrowDisputed,
+    )]
+    pub job_account: Account<'info, Job>,
+    
+    #[account(
+        mut,
+        close = client,
+        seeds = [b"escrow", job_account.key().as_ref()],
+        bump = escrow_account.bump,
+        constraint = escrow_account.job == job_account.key() @ ErrorCode::InvalidEscrow,
+        constraint = escrow_account.status == EscrowStatus::Released
+            || escrow_account.status == EscrowStatus::Refunded
+            @ ErrorCode::EscrowNotFinished,
+    )]
+    pub escrow_account: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        close = client,
+        seeds = [b"vault", escrow_account.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, EscrowVault>,
+    
+    // Optional token accounts
+    pub token_program: Option<Program<'info, Token>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = escrow_account.token_mint.unwrap(),
+        associated_token::authority = escrow_account,
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        token::mint = escrow_account.token_mint.unwrap(),
+        token::authority = client,
+    )]
+    pub client_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn create_escrow(
+    ctx: Context<CreateEscrow>,
+    amount: u64,
+    token_mint: Option<Pubkey>,
+    initial_deposit: u64,
+) -> Result<()> {
+    let job = &mut ctx.accounts.job_account;
+    let escrow = &mut ctx.accounts.escrow_account;
+    let client = &ctx.accounts.client;
+    let clock = Clock::get()?;
+    
+    // Validate inputs
+    if amount == 0 {
+        return err!(ErrorCode::InvalidAmount);
     }
     
-    // Update escrow status
-    escrow.status = EscrowStatus::Released;
+    // The escrow must match the deal the freelancer won
+    if amount != ctx.accounts.accepted_bid.amount {
+        return err!(ErrorCode::EscrowAmountMismatch);
+    }
+    
+    if token_mint != job.currency {
+        return err!(ErrorCode::EscrowMintMismatch);
+    }
+    
+    if token_mint != ctx.accounts.token_mint.as_ref().map(|mint| mint.key()) {
+        return err!(ErrorCode::EscrowMintMismatch);
+    }
+    
+    // Large contracts can start partially funded and be topped up with `fund_escrow`
+    if initial_deposit > amount {
+        return err!(ErrorCode::InvalidAmount);
+    }
+    
+    // Initialize escrow
+    escrow.job = job.key();
+    escrow.client = client.key();
+    escrow.freelancer = job.freelancer.unwrap();
+    escrow.amount = amount;
+    escrow.total_deposited = initial_deposit;
+    escrow.total_released = 0;
+    escrow.status = EscrowStatus::Active;
+    escrow.token_mint = token_mint;
+    escrow.created_at = clock.unix_timestamp;
     escrow.updated_at = clock.unix_timestamp;
+    escrow.completed_milestones = Vec::new();
+    escrow.dispute_bond = 0;
+    escrow.locked_amount = 0;
+    escrow.cancellation_offer = None;
+    escrow.bump = ctx.bumps.escrow_account;
     
-    // Update job status
-    job.status = JobStatus::Completed;
+    let vault = &mut ctx.accounts.vault;
+    vault.escrow = escrow.key();
+    vault.bump = ctx.bumps.vault;
+    
+    // Update job with escrow reference
+    job.escrow = Some(escrow.key());
     job.updated_at = clock.unix_timestamp;
     
-    // Transfer funds from escrow to freelancer
-    if let Some(token_mint) = escrow.token_mint {
-        // Transfer SPL tokens
-        let escrow_token_account = ctx.accounts.escrow_token_account.as_ref().unwrap();
-        let freelancer_token_account = ctx.accounts.freelancer_token_account.as_ref().unwrap();
-        let token_program = ctx.accounts.token_program.as_ref().unwrap();
-        
-        let transfer_instruction = Transfer {
-            from: escrow_token_account.to_account_info(),
-            to: freelancer_token_account.to_account_info(),
-            authority: escrow.to_account_info(),
-        };
-        
-        let seeds = &[
-            b"escrow",
-            escrow.job.as_ref(),
-            &[1], // bump seed
-        ];
-        let signer = &[&seeds[..]];
-        
-        token::transfer(
-            CpiContext::new_with_signer(
-                token_program.to_account_info(),
-                transfer_instruction,
-                signer,
-            ),
-            amount_to_release,
-        )?;
-    } else {
-        // Transfer SOL
-        if escrow.to_account_info().lamports() < amount_to_release {
-            return err!(ErrorCode::InsufficientFunds);
-        }
-        
-        **freelancer.to_account_info().try_borrow_mut_lamports()? += amount_to_release;
-        **escrow.to_account_info().try_borrow_mut_lamports()? -= amount_to_release;
+    // Transfer funds from client to escrow
+    let depositor = EscrowDepositor {
+        escrow,
+        depositor: client,
+        vault: &ctx.accounts.vault,
+        system_program: &ctx.accounts.system_program,
+        depositor_token_account: ctx.accounts.client_token_account.as_ref(),
+        escrow_token_account: ctx.accounts.escrow_token_account.as_ref(),
+        token_program: ctx.accounts.token_program.as_ref(),
+    };
+    depositor.deposit(initial_deposit)?;
+    
+    // Emit event
+    emit!(EscrowCreated {
+        job: job.key(),
+        client: client.key(),
+        freelancer: escrow.freelancer,
+        amount,
+        is_token: token_mint.is_some(),
+    });
+    
+    if initial_deposit > 0 {
+        emit!(EscrowFunded {
+            job: job.key(),
+            escrow: escrow.key(),
+            client: client.key(),
+            amount: initial_deposit,
+            total_deposited: escrow.total_deposited,
+        });
     }
     
+    Ok(())
+}
+
+pub fn fund_escrow(ctx: Context<FundEscrow>, _job_id: u64, amount: u64) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_account;
+    let client = &ctx.accounts.client;
+    
+    if amount == 0 {
+        return err!(ErrorCode::InvalidAmount);
+    }
+    
+    escrow.total_deposited = escrow.total_deposited.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    
+    // Deposits beyond the contract value are change orders and raise it
+    if escrow.total_deposited > escrow.amount {
+        escrow.amount = escrow.total_deposited;
+    }
+    escrow.updated_at = Clock::get()?.unix_timestamp;
+    
+    let depositor = EscrowDepositor {
+        escrow,
+        depositor: client,
+        vault: &ctx.accounts.vault,
+        system_program: &ctx.accounts.system_program,
+        depositor_token_account: ctx.accounts.client_token_account.as_ref(),
+        escrow_token_account: ctx.accounts.escrow_token_account.as_ref(),
+        token_program: ctx.accounts.token_program.as_ref(),
+    };
+    depositor.deposit(amount)?;
+    
+    emit!(EscrowFunded {
+        job: ctx.accounts.job_account.key(),
+        escrow: escrow.key(),
+        client: client.key(),
+        amount,
+        total_deposited: escrow.total_deposited,
+    });
+    
+    Ok(())
+}
+
+pub fn release_escrow(ctx: Context<ReleaseEscrow>, _job_id: u64) -> Result<()> {
+    let job = &mut ctx.accounts.job_account;
+    let escrow = &mut ctx.accounts.escrow_account;
+    let freelancer = &ctx.accounts.freelancer;
+    let clock = Clock::get()?;
+    
+    let amount_to_release = record_release(job, escrow, clock.unix_timestamp)?;
+    
+    // Transfer funds from escrow to freelancer, less the platform fee
+    let payer = EscrowPayer {
+        escrow,
+        vault: &ctx.accounts.vault,
+        escrow_token_account: ctx.accounts.escrow_token_account.as_ref(),
+        token_program: ctx.accounts.token_program.as_ref(),
+    };
+    let fee = payer.pay_with_fee(
+        &ctx.accounts.config,
+        freelancer,
+        ctx.accounts.freelancer_token_account.as_ref(),
+        &ctx.accounts.treasury,
+        ctx.accounts.treasury_token_account.as_ref(),
+        amount_to_release,
+    )?;
+    
     // Emit event
     emit!(EscrowReleased {
         job: job.key(),
         client: escrow.client,
         freelancer: escrow.freelancer,
         amount: amount_to_release,
+        fee,
     });
     
     Ok(())
@@ -415,7 +943,7 @@ pub fn release_escrow(ctx: Context<ReleaseEscrow>, job_id: u64) -> Result<()> {
 
 pub fn milestone_payment(
     ctx: Context<MilestonePayment>,
-    job_id: u64,
+    _job_id: u64,
     milestone_index: u8,
 ) -> Result<()> {
     let job = &mut ctx.accounts.job_account;
@@ -423,108 +951,219 @@ pub fn milestone_payment(
     let freelancer = &ctx.accounts.freelancer;
     let clock = Clock::get()?;
     
-    // Validate milestone index
-    let milestones = job.milestones.as_ref().unwrap();
-    if milestone_index as usize >= milestones.len() {
-        return err!(ErrorCode::InvalidMilestoneIndex);
+    // Payment is the client's approval of the submitted deliverable
+    let amount_to_release = record_milestone_approval(job, escrow, milestone_index, clock.unix_timestamp)?;
+    
+    // Transfer funds from escrow to freelancer, less the platform fee
+    let payer = EscrowPayer {
+        escrow,
+        vault: &ctx.accounts.vault,
+        escrow_token_account: ctx.accounts.escrow_token_account.as_ref(),
+        token_program: ctx.accounts.token_program.as_ref(),
+    };
+    let fee = payer.pay_with_fee(
+        &ctx.accounts.config,
+        freelancer,
+        ctx.accounts.freelancer_token_account.as_ref(),
+        &ctx.accounts.treasury,
+        ctx.accounts.treasury_token_account.as_ref(),
+        amount_to_release,
+    )?;
+    
+    // Emit event
+    emit!(MilestoneCompleted {
+        job: job.key(),
+        escrow: escrow.key(),
+        milestone_index,
+        amount: amount_to_release,
+        fee,
+    });
+    
+    Ok(())
+}
+
+pub fn claim_after_timeout(
+    ctx: Context<ClaimAfterTimeout>,
+    _job_id: u64,
+    milestone_index: Option<u8>,
+) -> Result<()> {
+    let job = &mut ctx.accounts.job_account;
+    let escrow = &mut ctx.accounts.escrow_account;
+    let freelancer = &ctx.accounts.freelancer;
+    let now = Clock::get()?.unix_timestamp;
+    
+    // Submitted work counts as approved once the client's review period lapses
+    let submitted_at = match milestone_index {
+        Some(index) => {
+            let milestone = job
+                .milestones
+                .as_ref()
+                .and_then(|milestones| milestones.get(index as usize))
+                .ok_or(ErrorCode::InvalidMilestoneIndex)?;
+            if milestone.status != MilestoneStatus::Submitted {
+                return err!(ErrorCode::MilestoneNotSubmitted);
+            }
+            milestone.submitted_at
+        }
+        None => {
+            if job.has_milestones {
+                return err!(ErrorCode::InvalidMilestoneIndex);
+            }
+            job.work_submitted_at
+        }
     }
+    .ok_or(ErrorCode::WorkNotSubmitted)?;
+    
+    if now < submitted_at.saturating_add(job.review_period) {
+        return err!(ErrorCode::ReviewPeriodActive);
+    }
+    
+    let amount_to_release = match milestone_index {
+        Some(index) => record_milestone_approval(job, escrow, index, now)?,
+        None => record_release(job, escrow, now)?,
+    };
+    
+    // Transfer funds from escrow to freelancer, less the platform fee
+    let payer = EscrowPayer {
+        escrow,
+        vault: &ctx.accounts.vault,
+        escrow_token_account: ctx.accounts.escrow_token_account.as_ref(),
+        token_program: ctx.accounts.token_program.as_ref(),
+    };
+    let fee = payer.pay_with_fee(
+        &ctx.accounts.config,
+        freelancer,
+        ctx.accounts.freelancer_token_account.as_ref(),
+        &ctx.accounts.treasury,
+        ctx.accounts.treasury_token_account.as_ref(),
+        amount_to_release,
+    )?;
+    
+    // Emit event
+    match milestone_index {
+        Some(index) => emit!(MilestoneCompleted {
+            job: job.key(),
+            escrow: escrow.key(),
+            milestone_index: index,
+            amount: amount_to_release,
+            fee,
+        }),
+        None => emit!(EscrowReleased {
+            job: job.key(),
+            client: escrow.client,
+            freelancer: escrow.freelancer,
+            amount: amount_to_release,
+            fee,
+        }),
+    }
+    
+    emit!(ClaimedAfterTimeout {
+        job: job.key(),
+        caller: ctx.accounts.caller.key(),
+        milestone_index,
+        amount: amount_to_release,
+    });
+    
+    Ok(())
+}
+
+// Marks the job completed and records the final payout. Returns the amount to release.
+fn record_release(job: &mut Job, escrow: &mut Escrow, now: i64) -> Result<u64> {
+    // Release whatever part of the contract value has not been paid out yet
+    let amount_to_release = escrow.amount.saturating_sub(escrow.total_released);
+    escrow.record_payout(amount_to_release)?;
+    
+    // Update escrow status
+    escrow.status = EscrowStatus::Released;
+    escrow.updated_at = now;
+    
+    // Update job status
+    job.status = JobStatus::Completed;
+    job.completed_at = Some(now);
+    job.updated_at = now;
+    
+    Ok(amount_to_release)
+}
+
+// Approves a submitted milestone and records its payout. Returns the amount to release.
+fn record_milestone_approval(
+    job: &mut Job,
+    escrow: &mut Escrow,
+    milestone_index: u8,
+    now: i64,
+) -> Result<u64> {
+    // Validate milestone index
+    let milestones = job.milestones.as_ref().ok_or(ErrorCode::JobDoesNotHaveMilestones)?;
+    let milestone_count = milestones.len();
+    
+    // Calculate milestone amount; the approval that completes the plan takes whatever
+    // is left so the escrow always ends at exactly zero
+    let completes_plan = escrow.completed_milestones.len() + 1 == milestone_count;
+    let amount_to_release = if completes_plan {
+        escrow.amount.checked_sub(escrow.total_released).ok_or(ErrorCode::MathOverflow)?
+    } else {
+        milestone_amount(escrow.amount, milestones, milestone_index as usize)?
+    };
+    
+    let milestone = job
+        .milestones
+        .as_mut()
+        .and_then(|milestones| milestones.get_mut(milestone_index as usize))
+        .ok_or(ErrorCode::InvalidMilestoneIndex)?;
     
     // Check if milestone is already completed
     if escrow.completed_milestones.contains(&milestone_index) {
         return err!(ErrorCode::MilestoneAlreadyPaid);
     }
     
-    // Calculate milestone amount
-    let milestone = &milestones[milestone_index as usize];
-    let amount_to_release = (escrow.amount * milestone.amount as u64) / 10000;
+    if milestone.status != MilestoneStatus::Submitted {
+        return err!(ErrorCode::MilestoneNotSubmitted);
+    }
+    
+    // Update milestone as approved in job
+    milestone.status = MilestoneStatus::Approved;
+    milestone.approved_at = Some(now);
+    job.updated_at = now;
     
     // Add milestone to completed list
+    escrow.record_payout(amount_to_release)?;
     escrow.completed_milestones.push(milestone_index);
-    escrow.updated_at = clock.unix_timestamp;
-    
-    // Update milestone as completed in job
-    if let Some(ref mut milestones) = job.milestones {
-        milestones[milestone_index as usize].completed = true;
-    }
-    job.updated_at = clock.unix_timestamp;
-    
-    // Transfer funds from escrow to freelancer
-    if let Some(token_mint) = escrow.token_mint {
-        // Transfer SPL tokens
-        let escrow_token_account = ctx.accounts.escrow_token_account.as_ref().unwrap();
-        let freelancer_token_account = ctx.accounts.freelancer_token_account.as_ref().unwrap();
-        let token_program = ctx.accounts.token_program.as_ref().unwrap();
-        
-        let transfer_instruction = Transfer {
-            from: escrow_token_account.to_account_info(),
-            to: freelancer_token_account.to_account_info(),
-            authority: escrow.to_account_info(),
-        };
-        
-        let seeds = &[
-            b"escrow",
-            escrow.job.as_ref(),
-            &[1], // bump seed
-        ];
-        let signer = &[&seeds[..]];
-        
-        token::transfer(
-            CpiContext::new_with_signer(
-                token_program.to_account_info(),
-                transfer_instruction,
-                signer,
-            ),
-            amount_to_release,
-        )?;
-    } else {
-        // Transfer SOL
-        if escrow.to_account_info().lamports() < amount_to_release {
-            return err!(ErrorCode::InsufficientFunds);
-        }
-        
-        **freelancer.to_account_info().try_borrow_mut_lamports()? += amount_to_release;
-        **escrow.to_account_info().try_borrow_mut_lamports()? -= amount_to_release;
-    }
+    escrow.updated_at = now;
     
     // Check if all milestones are completed, and if so, update job status
-    if escrow.completed_milestones.len() == milestones.len() {
+    if completes_plan {
         job.status = JobStatus::Completed;
+        job.completed_at = Some(now);
         escrow.status = EscrowStatus::Released;
     }
     
-    // Emit event
-    emit!(MilestoneCompleted {
-        job: job.key(),
-        escrow: escrow.key(),
-        milestone_index,
-        amount: amount_to_release,
-    });
-    
-    Ok(())
+    Ok(amount_to_release)
 }
 
-pub fn refund_escrow(ctx: Context<RefundEscrow>, job_id: u64) -> Result<()> {
+pub fn refund_escrow(ctx: Context<RefundEscrow>, _job_id: u64) -> Result<()> {
     let job = &mut ctx.accounts.job_account;
     let escrow = &mut ctx.accounts.escrow_account;
     let client = &ctx.accounts.client;
     let clock = Clock::get()?;
     
-    // Calculate amount to refund (remaining amount after any milestone payments)
-    let mut refund_amount = escrow.amount;
-    
-    if job.has_milestones && !escrow.completed_milestones.is_empty() {
-        // Calculate remaining amount
-        let milestones = job.milestones.as_ref().unwrap();
-        let paid_amount: u64 = escrow.completed_milestones.iter()
-            .map(|&idx| {
-                let milestone = &milestones[idx as usize];
-                (escrow.amount * milestone.amount as u64) / 10000
-            })
-            .sum();
+    // Once the freelancer has accepted the contract, the client can only take the
+    // escrow back alone after the deadline and grace period, and not while work
+    // or a milestone is waiting for review. Otherwise it takes a mutual cancellation.
+    if job.contract_accepted_at.is_some() {
+        let refundable_at = job.deadline.checked_add(REFUND_GRACE_PERIOD).ok_or(ErrorCode::MathOverflow)?;
+        require!(clock.unix_timestamp > refundable_at, ErrorCode::RefundLocked);
+        require!(job.work_submitted_at.is_none(), ErrorCode::RefundLocked);
         
-        refund_amount = escrow.amount.saturating_sub(paid_amount);
+        let milestone_in_review = job.milestones.as_ref().is_some_and(|milestones| {
+            milestones.iter().any(|milestone| milestone.status == MilestoneStatus::Submitted)
+        });
+        require!(!milestone_in_review, ErrorCode::RefundLocked);
     }
     
+    // Refund everything deposited that has not been paid out
+    let refund_amount = escrow.available();
+    escrow.record_payout(refund_amount)?;
+    
     // Update escrow status
     escrow.status = EscrowStatus::Refunded;
     escrow.updated_at = clock.unix_timestamp;
@@ -536,48 +1175,362 @@ pub fn refund_escrow(ctx: Context<RefundEscrow>, job_id: u64) -> Result<()> {
     job.updated_at = clock.unix_timestamp;
     
     // Transfer funds from escrow to client
-    if let Some(token_mint) = escrow.token_mint {
-        // Transfer SPL tokens
-        let escrow_token_account = ctx.accounts.escrow_token_account.as_ref().unwrap();
-        let client_token_account = ctx.accounts.client_token_account.as_ref().unwrap();
-        let token_program = ctx.accounts.token_program.as_ref().unwrap();
+    let payer = EscrowPayer {
+        escrow,
+        vault: &ctx.accounts.vault,
+        escrow_token_account: ctx.accounts.escrow_token_account.as_ref(),
+        token_program: ctx.accounts.token_program.as_ref(),
+    };
+    payer.pay(
+        &client.to_account_info(),
+        ctx.accounts.client_token_account.as_ref(),
+        refund_amount,
+    )?;
+    
+    // Emit event
+    emit!(EscrowRefunded {
+        job: job.key(),
+        client: escrow.client,
+        amount: refund_amount,
+    });
+    
+    Ok(())
+}
+
+pub fn propose_cancellation(
+    ctx: Context<ProposeCancellation>,
+    _job_id: u64,
+    freelancer_bps: u16,
+) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow_account;
+    let proposer = ctx.accounts.proposer.key();
+    
+    require!(freelancer_bps <= 10_000, ErrorCode::InvalidSplit);
+    
+    // A new offer replaces any earlier one
+    escrow.cancellation_offer = Some(SplitOffer {
+        proposer,
+        freelancer_bps,
+        proposed_at: Clock::get()?.unix_timestamp,
+    });
+    
+    emit!(CancellationProposed {
+        job: ctx.accounts.job_account.key(),
+        proposer,
+        freelancer_bps,
+    });
+    
+    Ok(())
+}
+
+pub fn accept_cancellation(
+    ctx: Context<AcceptCancellation>,
+    _job_id: u64,
+    freelancer_bps: u16,
+) -> Result<()> {
+    let job = &mut ctx.accounts.job_account;
+    let escrow = &mut ctx.accounts.escrow_account;
+    let acceptor = ctx.accounts.acceptor.key();
+    let clock = Clock::get()?;
+    
+    SplitOffer::check_acceptance(
+        escrow.cancellation_offer.as_ref(),
+        acceptor,
+        freelancer_bps,
+        ErrorCode::NoCancellationOffer,
+        ErrorCode::OwnCancellationOffer,
+        ErrorCode::CancellationOfferChanged,
+    )?;
+    
+    // Split everything deposited that has not been paid out
+    let remaining = escrow.available();
+    let freelancer_amount = (remaining as u128)
+        .checked_mul(freelancer_bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / 10_000;
+    let freelancer_amount = u64::try_from(freelancer_amount).map_err(|_| error!(ErrorCode::MathOverflow))?;
+    let client_amount = remaining.checked_sub(freelancer_amount).ok_or(ErrorCode::MathOverflow)?;
+    escrow.record_payout(remaining)?;
+    
+    // Update escrow status
+    escrow.status = EscrowStatus::Refunded;
+    escrow.cancellation_offer = None;
+    escrow.updated_at = clock.unix_timestamp;
+    
+    // Update job status
+    job.status = JobStatus::Cancelled;
+    job.updated_at = clock.unix_timestamp;
+    
+    // Pay both sides; the platform fee only applies to the freelancer's share
+    let payer = EscrowPayer {
+        escrow,
+        vault: &ctx.accounts.vault,
+        escrow_token_account: ctx.accounts.escrow_token_account.as_ref(),
+        token_program: ctx.accounts.token_program.as_ref(),
+    };
+    let fee = payer.pay_with_fee(
+        &ctx.accounts.config,
+        &ctx.accounts.freelancer,
+        ctx.accounts.freelancer_token_account.as_ref(),
+        &ctx.accounts.treasury,
+        ctx.accounts.treasury_token_account.as_ref(),
+        freelancer_amount,
+    )?;
+    payer.pay(
+        &ctx.accounts.client,
+        ctx.accounts.client_token_account.as_ref(),
+        client_amount,
+    )?;
+    
+    // Emit event
+    emit!(ContractCancelled {
+        job: job.key(),
+        client: escrow.client,
+        freelancer: escrow.freelancer,
+        freelancer_amount,
+        client_amount,
+        fee,
+    });
+    
+    Ok(())
+}
+
+// Share of `total` owed for a milestone. The final milestone takes the rounding
+// remainder so the milestones always add up to exactly `total`.
+pub(crate) fn milestone_amount(total: u64, milestones: &[Milestone], index: usize) -> Result<u64> {
+    let share = |milestone: &Milestone| -> Result<u64> {
+        let amount = (total as u128)
+            .checked_mul(milestone.amount as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / TOTAL_MILESTONE_BPS as u128;
+        u64::try_from(amount).map_err(|_| error!(ErrorCode::MathOverflow))
+    };
+    
+    if index >= milestones.len() {
+        return err!(ErrorCode::InvalidMilestoneIndex);
+    }
+    
+    if index + 1 < milestones.len() {
+        return share(&milestones[index]);
+    }
+    
+    let mut others: u64 = 0;
+    for milestone in &milestones[..index] {
+        others = others.checked_add(share(milestone)?).ok_or(ErrorCode::MathOverflow)?;
+    }
+    total.checked_sub(others).ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+// Moves funds into an escrow: SOL into the vault, SPL tokens into the escrow ATA
+pub(crate) struct EscrowDepositor<'a, 'info> {
+    pub escrow: &'a Account<'info, Escrow>,
+    pub depositor: &'a Signer<'info>,
+    pub vault: &'a Account<'info, EscrowVault>,
+    pub system_program: &'a Program<'info, System>,
+    pub depositor_token_account: Option<&'a Account<'info, TokenAccount>>,
+    pub escrow_token_account: Option<&'a Account<'info, TokenAccount>>,
+    pub token_program: Option<&'a Program<'info, Token>>,
+}
+
+impl<'a, 'info> EscrowDepositor<'a, 'info> {
+    pub fn deposit(&self, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        
+        if self.escrow.token_mint.is_some() {
+            // Transfer SPL tokens
+            let depositor_token_account = self.depositor_token_account.ok_or(ErrorCode::MissingTokenAccount)?;
+            let escrow_token_account = self.escrow_token_account.ok_or(ErrorCode::MissingTokenAccount)?;
+            let token_program = self.token_program.ok_or(ErrorCode::MissingTokenAccount)?;
+            
+            let transfer_instruction = Transfer {
+                from: depositor_token_account.to_account_info(),
+                to: escrow_token_account.to_account_info(),
+                authority: self.depositor.to_account_info(),
+            };
+            
+            token::transfer(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    transfer_instruction,
+                ),
+                amount,
+            )
+        } else {
+            // Transfer SOL into the vault; the depositor is not owned by this program,
+            // so this has to go through the System Program
+            if self.depositor.lamports() < amount {
+                return err!(ErrorCode::InsufficientFunds);
+            }
+            
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: self.depositor.to_account_info(),
+                        to: self.vault.to_account_info(),
+                    },
+                ),
+                amount,
+            )
+        }
+    }
+}
+
+// Moves funds out of an escrow: SOL from the vault, SPL tokens from the escrow ATA
+pub(crate) struct EscrowPayer<'a, 'info> {
+    pub escrow: &'a Account<'info, Escrow>,
+    pub vault: &'a Account<'info, EscrowVault>,
+    pub escrow_token_account: Option<&'a Account<'info, TokenAccount>>,
+    pub token_program: Option<&'a Program<'info, Token>>,
+}
+
+impl<'a, 'info> EscrowPayer<'a, 'info> {
+    // Sends `amount` to the recipient wallet (SOL) or its token account (SPL)
+    pub fn pay(
+        &self,
+        recipient: &AccountInfo<'info>,
+        recipient_token_account: Option<&Account<'info, TokenAccount>>,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        
+        if self.escrow.token_mint.is_some() {
+            let escrow_token_account = self.escrow_token_account.ok_or(ErrorCode::MissingTokenAccount)?;
+            let recipient_token_account = recipient_token_account.ok_or(ErrorCode::MissingTokenAccount)?;
+            let token_program = self.token_program.ok_or(ErrorCode::MissingTokenAccount)?;
+            
+            transfer_from_escrow_tokens(
+                self.escrow,
+                escrow_token_account,
+                &recipient_token_account.to_account_info(),
+                token_program,
+                amount,
+            )
+        } else {
+            transfer_from_vault(&self.vault.to_account_info(), recipient, amount)
+        }
+    }
+    
+    // Pays `amount` to the recipient, routing the platform fee to the treasury.
+    // Returns the fee that was charged.
+    pub fn pay_with_fee(
+        &self,
+        config: &Config,
+        recipient: &AccountInfo<'info>,
+        recipient_token_account: Option<&Account<'info, TokenAccount>>,
+        treasury: &AccountInfo<'info>,
+        treasury_token_account: Option<&Account<'info, TokenAccount>>,
+        amount: u64,
+    ) -> Result<u64> {
+        let fee = config.fee_for(amount)?;
+        let net_amount = amount.checked_sub(fee).ok_or(ErrorCode::MathOverflow)?;
+        
+        self.pay(treasury, treasury_token_account, fee)?;
+        self.pay(recipient, recipient_token_account, net_amount)?;
+        
+        Ok(fee)
+    }
+}
+
+// Moves tokens out of the escrow's ATA, signed by the escrow PDA
+pub(crate) fn transfer_from_escrow_tokens<'info>(
+    escrow: &Account<'info, Escrow>,
+    escrow_token_account: &Account<'info, TokenAccount>,
+    recipient_token_account: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = escrow.signer_seeds();
+    let signer = &[&seeds[..]];
+    
+    let transfer_instruction = Transfer {
+        from: escrow_token_account.to_account_info(),
+        to: recipient_token_account.clone(),
+        authority: escrow.to_account_info(),
+    };
+    
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            transfer_instruction,
+            signer,
+        ),
+        amount,
+    )
+}
+
+// Moves escrowed lamports out of the vault without touching its rent-exempt reserve
+pub(crate) fn transfer_from_vault<'info>(
+    vault: &AccountInfo<'info>,
+    recipient: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let rent_reserve = Rent::get()?.minimum_balance(vault.data_len());
+    let available = vault.lamports().saturating_sub(rent_reserve);
+    
+    if available < amount {
+        return err!(ErrorCode::InsufficientFunds);
+    }
+    
+    **vault.try_borrow_mut_lamports()? -= amount;
+    **recipient.try_borrow_mut_lamports()? += amount;
+    
+    Ok(())
+}
+
+pub fn close_escrow(ctx: Context<CloseEscrow>) -> Result<()> {
+    let job = &mut ctx.accounts.job_account;
+    let escrow = &ctx.accounts.escrow_account;
+    let client = &ctx.accounts.client;
+    
+    // Close the escrow token account so its rent also goes back to the client
+    if escrow.token_mint.is_some() {
+        let escrow_token_account = ctx.accounts.escrow_token_account.as_ref().ok_or(ErrorCode::MissingTokenAccount)?;
+        let token_program = ctx.accounts.token_program.as_ref().ok_or(ErrorCode::MissingTokenAccount)?;
         
-        let transfer_instruction = Transfer {
-            from: escrow_token_account.to_account_info(),
-            to: client_token_account.to_account_info(),
+        // Anyone can send tokens to the ATA, so sweep whatever is left to the
+        // client rather than letting a stray balance block the close
+        if escrow_token_account.amount > 0 {
+            let client_token_account = ctx.accounts.client_token_account.as_ref().ok_or(ErrorCode::MissingTokenAccount)?;
+            transfer_from_escrow_tokens(
+                escrow,
+                escrow_token_account,
+                &client_token_account.to_account_info(),
+                token_program,
+                escrow_token_account.amount,
+            )?;
+        }
+        
+        let close_instruction = CloseAccount {
+            account: escrow_token_account.to_account_info(),
+            destination: client.to_account_info(),
             authority: escrow.to_account_info(),
         };
         
-        let seeds = &[
-            b"escrow",
-            escrow.job.as_ref(),
-            &[1], // bump seed
-        ];
+        let seeds = escrow.signer_seeds();
         let signer = &[&seeds[..]];
         
-        token::transfer(
+        token::close_account(
             CpiContext::new_with_signer(
                 token_program.to_account_info(),
-                transfer_instruction,
+                close_instruction,
                 signer,
             ),
-            refund_amount,
         )?;
-    } else {
-        // Transfer SOL
-        if escrow.to_account_info().lamports() < refund_amount {
-            return err!(ErrorCode::InsufficientFunds);
-        }
-        
-        **client.to_account_info().try_borrow_mut_lamports()? += refund_amount;
-        **escrow.to_account_info().try_borrow_mut_lamports()? -= refund_amount;
     }
     
-    // Emit event
-    emit!(EscrowRefunded {
+    // Unlink the escrow so the job itself can be closed
+    job.escrow = None;
+    job.updated_at = Clock::get()?.unix_timestamp;
+    
+    emit!(EscrowClosed {
         job: job.key(),
-        client: escrow.client,
-        amount: refund_amount,
+        escrow: escrow.key(),
+        client: client.key(),
     });
     
     Ok(())
@@ -620,4 +1573,58 @@ pub enum ErrorCode {
     
     #[msg("Milestone already paid")]
     MilestoneAlreadyPaid,
+    
+    #[msg("Milestone has not been submitted for approval")]
+    MilestoneNotSubmitted,
+    
+    #[msg("Escrow is under dispute")]
+    EscrowDisputed,
+    
+    #[msg("Escrow has not been released or refunded")]
+    EscrowNotFinished,
+    
+    #[msg("Treasury does not match the marketplace config")]
+    InvalidTreasury,
+    
+    #[msg("Token account required for SPL escrows was not provided")]
+    MissingTokenAccount,
+    
+    #[msg("Math overflow")]
+    MathOverflow,
+    
+    #[msg("Bid is not the accepted bid for this job")]
+    InvalidBid,
+    
+    #[msg("Escrow amount does not match the accepted bid")]
+    EscrowAmountMismatch,
+    
+    #[msg("Escrow mint does not match the job currency")]
+    EscrowMintMismatch,
+    
+    #[msg("Escrow does not hold enough funds for this payout")]
+    EscrowUnderfunded,
+    
+    #[msg("Work has not been submitted")]
+    WorkNotSubmitted,
+    
+    #[msg("Client review period has not elapsed")]
+    ReviewPeriodActive,
+    
+    #[msg("A milestone of this escrow is under dispute")]
+    MilestoneDisputed,
+    
+    #[msg("Freelancer has accepted the contract; cancel it mutually or wait for the refund timeout")]
+    RefundLocked,
+    
+    #[msg("Split must be between 0 and 10000 basis points")]
+    InvalidSplit,
+    
+    #[msg("No cancellation offer to accept")]
+    NoCancellationOffer,
+    
+    #[msg("Cannot accept your own cancellation offer")]
+    OwnCancellationOffer,
+    
+    #[msg("Cancellation offer does not match the accepted split")]
+    CancellationOfferChanged,
 } 
\ No newline at end of file
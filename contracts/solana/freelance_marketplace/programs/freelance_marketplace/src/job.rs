@@ -1,7 +1,15 @@
 use anchor_lang::prelude::*;
-use crate::escrow::PaymentType;
-use std::collections::BTreeMap;
-use crate::escrow::Escrow;
+
+// How long both parties can leave reviews after a job completes
+pub const REVIEW_WINDOW: i64 = 14 * 24 * 60 * 60;
+
+// Bounds on how long a client has to review submitted work before it can be claimed
+pub const MIN_REVIEW_PERIOD: i64 = 24 * 60 * 60;
+pub const MAX_REVIEW_PERIOD: i64 = 30 * 24 * 60 * 60;
+
+// Milestone plans split the whole contract value across at most this many milestones
+pub const MAX_MILESTONES: usize = 10;
+pub const TOTAL_MILESTONE_BPS: u64 = 10_000;
 
 #[account]
 #[derive(Default)]
@@ -12,18 +20,78 @@ pub struct Job {
     pub description: String,
     pub budget: u64,
     pub deadline: i64,
-    pub currency: String,
+    pub currency: Option<Pubkey>, // None means SOL, Some(pubkey) means SPL token mint
     pub category: String,
     pub required_skills: Vec<String>,
     pub status: JobStatus,
-    pub visibility: u8,
-    pub bids: Vec<Bid>,
+    pub visibility: JobVisibility,
+    pub accepted_bid: Option<Pubkey>,
+    pub terms_version: u32,
     pub freelancer: Option<Pubkey>,
+    pub contract_accepted_at: Option<i64>, // When the freelancer accepted the funded contract
+    pub escrow: Option<Pubkey>,
     pub created_at: i64,
     pub updated_at: i64,
     pub completed_at: Option<i64>,
     pub has_milestones: bool,
     pub milestones: Option<Vec<Milestone>>,
+    pub review_period: i64, // Seconds the client has to review submitted work
+    pub work_submitted_at: Option<i64>,
+    pub deliverable_hash: Option<[u8; 32]>, // Content hash of the submitted work for jobs without milestones
+    pub bump: u8,
+}
+
+impl Job {
+    pub fn space(
+        title: &str,
+        description: &str,
+        category: &str,
+        required_skills: &[String],
+        milestones: &Option<Vec<Milestone>>,
+    ) -> usize {
+        8 // account discriminator
+            + 8 // id
+            + 32 // client pubkey
+            + 4 + title.len() // title
+            + 4 + description.len() // description
+            + 8 // budget
+            + 8 // deadline
+            + 1 + 32 // currency (Option<Pubkey>)
+            + 4 + category.len() // category
+            + 4 + (required_skills.iter().map(|s| 4 + s.len()).sum::<usize>()) // required_skills
+            + 1 // status
+            + 1 // visibility
+            + 33 // accepted_bid
+            + 4 // terms_version
+            + 33 // freelancer
+            + 9 // contract_accepted_at
+            + 33 // escrow
+            + 8 // created_at
+            + 8 // updated_at
+            + 9 // completed_at
+            + 1 // has_milestones
+            + milestones_space(milestones) // milestones
+            + 8 // review_period
+            + 9 // work_submitted_at
+            + 1 + 32 // deliverable_hash
+            + 1 // bump
+    }
+}
+
+// Per-client counter used to derive job addresses
+#[account]
+#[derive(Default)]
+pub struct ClientState {
+    pub client: Pubkey,
+    pub job_count: u64,
+    pub bump: u8,
+}
+
+impl ClientState {
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // client
+        8 + // job_count
+        1; // bump
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
@@ -32,7 +100,8 @@ pub enum JobStatus {
     InProgress,
     Completed,
     Cancelled,
-    Disputed
+    Disputed,
+    Expired
 }
 
 impl Default for JobStatus {
@@ -45,7 +114,8 @@ impl Default for JobStatus {
 pub enum BidStatus {
     Pending,
     Accepted,
-    Rejected
+    Rejected,
+    Withdrawn
 }
 
 impl Default for BidStatus {
@@ -54,33 +124,151 @@ impl Default for BidStatus {
     }
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum MilestoneStatus {
+    Pending,
+    Submitted,
+    Approved,
+    RevisionRequested,
+    Disputed,
+    Resolved // Paid out by a dispute ruling or settlement
+}
+
+impl Default for MilestoneStatus {
+    fn default() -> Self {
+        MilestoneStatus::Pending
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct Milestone {
     pub title: String,
     pub description: String,
     pub amount: u64, // percentage of total budget as basis points (1% = 100)
     pub deadline: i64,
-    pub completed: bool,
+    pub status: MilestoneStatus,
+    pub deliverable_hash: Option<[u8; 32]>, // Content hash of the latest submitted deliverable
+    pub submitted_at: Option<i64>,
+    pub approved_at: Option<i64>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+impl Milestone {
+    pub fn space(&self) -> usize {
+        4 + self.title.len() // title
+            + 4 + self.description.len() // description
+            + 8 // amount
+            + 8 // deadline
+            + 1 // status
+            + 1 + 32 // deliverable_hash
+            + 9 // submitted_at
+            + 9 // approved_at
+    }
+}
+
+// Checks that a milestone plan covers the whole contract with deadlines in order
+pub fn validate_milestones(milestones: &[Milestone], now: i64) -> Result<()> {
+    require!(
+        !milestones.is_empty() && milestones.len() <= MAX_MILESTONES,
+        JobError::InvalidMilestoneCount
+    );
+
+    let mut total_bps: u64 = 0;
+    let mut previous_deadline = now;
+    for milestone in milestones {
+        require!(milestone.amount > 0, JobError::InvalidMilestoneAmount);
+        require!(milestone.deadline > previous_deadline, JobError::InvalidMilestoneDeadline);
+
+        total_bps = total_bps
+            .checked_add(milestone.amount)
+            .ok_or(JobError::InvalidMilestoneAmount)?;
+        previous_deadline = milestone.deadline;
+    }
+
+    require!(total_bps == TOTAL_MILESTONE_BPS, JobError::InvalidMilestoneAmount);
+
+    Ok(())
+}
+
+// Serialized size of an optional milestone plan
+pub fn milestones_space(milestones: &Option<Vec<Milestone>>) -> usize {
+    1 + milestones
+        .as_ref()
+        .map_or(0, |m| 4 + m.iter().map(|milestone| milestone.space()).sum::<usize>())
+}
+
+// One bid per (job, bidder), stored at its own PDA
+#[account]
 pub struct Bid {
+    pub job: Pubkey,
     pub bidder: Pubkey,
     pub amount: u64,
     pub completion_time: i64,
     pub proposal: String,
     pub status: BidStatus,
     pub milestones: Option<Vec<Milestone>>,
-    pub timestamp: i64,
+    pub terms_version: u32,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Bid {
+    pub fn space(proposal: &str, milestones: &Option<Vec<Milestone>>) -> usize {
+        8 // account discriminator
+            + 32 // job
+            + 32 // bidder
+            + 8 // amount
+            + 8 // completion_time
+            + 4 + proposal.len() // proposal
+            + 1 // status
+            + milestones_space(milestones) // milestones
+            + 4 // terms_version
+            + 8 // created_at
+            + 1 // bump
+    }
+
+    // A bid is stale when the job's budget or deadline changed after it was placed
+    pub fn is_stale(&self, job: &Job) -> bool {
+        self.terms_version != job.terms_version
+    }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum JobVisibility {
     Public,
     Private,
     Invitation,
 }
 
+impl Default for JobVisibility {
+    fn default() -> Self {
+        JobVisibility::Public
+    }
+}
+
+impl JobVisibility {
+    // Private and invitation-only jobs only accept bids from invited freelancers
+    pub fn requires_invite(&self) -> bool {
+        *self != JobVisibility::Public
+    }
+}
+
+// Grants a freelancer permission to bid on a private or invitation-only job
+#[account]
+pub struct Invite {
+    pub job: Pubkey,
+    pub freelancer: Pubkey,
+    pub invited_at: i64,
+    pub bump: u8,
+}
+
+impl Invite {
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // job
+        32 + // freelancer
+        8 + // invited_at
+        1; // bump
+}
+
 // Context for job creation
 #[derive(Accounts)]
 #[instruction(
@@ -88,36 +276,63 @@ pub enum JobVisibility {
     description: String,
     budget: u64,
     deadline: i64,
-    currency: String,
+    currency: Option<Pubkey>,
     category: String,
     required_skills: Vec<String>,
-    visibility: u8
+    visibility: JobVisibility,
+    review_period: i64
 )]
 pub struct CreateJob<'info> {
     #[account(mut)]
     pub client: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = ClientState::SPACE,
+        seeds = [b"client_state", client.key().as_ref()],
+        bump,
+    )]
+    pub client_state: Account<'info, ClientState>,
     #[account(
         init,
         payer = client,
-        space = 8 // account discriminator
-            + 8 // id
-            + 32 // client pubkey
-            + 4 + title.len() // title
-            + 4 + description.len() // description
-            + 8 // budget
-            + 8 // deadline
-            + 4 + currency.len() // currency
-            + 4 + category.len() // category
-            + 4 + (required_skills.iter().map(|s| 4 + s.len()).sum::<usize>()) // required_skills
-            + 1 // status
-            + 1 // visibility
-            + 4 + (10 * (32 + 8 + 100 + 8 + 1 + 8 + 4 + 5 * 100)) // bids with milestones
-            + 33 // freelancer
-            + 8 // created_at
-            + 8 // updated_at
-            + 9 // completed_at
-            + 1 // has_milestones
-            + 4 + (5 * (4 + 50 + 4 + 100 + 8 + 8 + 1 + 1)) // milestones
+        seeds = [b"job", client.key().as_ref(), &client_state.job_count.to_le_bytes()],
+        bump,
+        space = Job::space(&title, &description, &category, &required_skills, &None),
+    )]
+    pub job: Account<'info, Job>,
+    pub system_program: Program<'info, System>,
+}
+
+// Context for editing an open job
+#[derive(Accounts)]
+#[instruction(
+    job_id: u64,
+    title: Option<String>,
+    description: Option<String>,
+    budget: Option<u64>,
+    deadline: Option<i64>,
+    category: Option<String>,
+    required_skills: Option<Vec<String>>
+)]
+pub struct UpdateJob<'info> {
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"job", client.key().as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
+        constraint = job.client == client.key(),
+        constraint = job.status == JobStatus::Open @ JobError::JobNotOpen,
+        realloc = Job::space(
+            title.as_deref().unwrap_or(&job.title),
+            description.as_deref().unwrap_or(&job.description),
+            category.as_deref().unwrap_or(&job.category),
+            required_skills.as_deref().unwrap_or(&job.required_skills),
+            &job.milestones,
+        ),
+        realloc::payer = client,
+        realloc::zero = false,
     )]
     pub job: Account<'info, Job>,
     pub system_program: Program<'info, System>,
@@ -130,26 +345,238 @@ pub struct PlaceBid<'info> {
     #[account(mut)]
     pub bidder: Signer<'info>,
     #[account(
-        mut,
-        constraint = job.id == job_id,
+        seeds = [b"job", job.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
         constraint = job.status == JobStatus::Open,
         constraint = job.deadline > Clock::get().unwrap().unix_timestamp,
     )]
     pub job: Account<'info, Job>,
+    #[account(
+        init,
+        payer = bidder,
+        space = Bid::space(&proposal, &milestones),
+        seeds = [b"bid", job.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    pub bid: Account<'info, Bid>,
+    // Required when the job is private or invitation-only
+    #[account(
+        seeds = [b"invite", job.key().as_ref(), bidder.key().as_ref()],
+        bump = invite.bump,
+    )]
+    pub invite: Option<Account<'info, Invite>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Context for inviting a freelancer to a job
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct InviteFreelancer<'info> {
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"job", client.key().as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
+        constraint = job.client == client.key(),
+        constraint = job.status == JobStatus::Open,
+    )]
+    pub job: Account<'info, Job>,
+    /// CHECK: Only used as a seed for the invite account
+    pub freelancer: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = client,
+        space = Invite::SPACE,
+        seeds = [b"invite", job.key().as_ref(), freelancer.key().as_ref()],
+        bump,
+    )]
+    pub invite: Account<'info, Invite>,
+    pub system_program: Program<'info, System>,
+}
+
+// Context for revoking an invitation
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct RevokeInvite<'info> {
+    #[account(mut)]
+    pub client: Signer<'info>,
+    /// CHECK: The job may already have been closed or expired; the seeds tie it to this client
+    #[account(
+        seeds = [b"job", client.key().as_ref(), &job_id.to_le_bytes()],
+        bump,
+        address = invite.job,
+    )]
+    pub job: AccountInfo<'info>,
+    #[account(
+        mut,
+        close = client,
+        seeds = [b"invite", job.key().as_ref(), invite.freelancer.as_ref()],
+        bump = invite.bump,
+    )]
+    pub invite: Account<'info, Invite>,
+}
+
+// Context for amending a pending bid
+#[derive(Accounts)]
+#[instruction(
+    job_id: u64,
+    bid_amount: Option<u64>,
+    completion_time: Option<i64>,
+    proposal: Option<String>,
+    milestones: Option<Vec<Milestone>>
+)]
+pub struct UpdateBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        seeds = [b"job", job.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
+        constraint = job.status == JobStatus::Open,
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        mut,
+        seeds = [b"bid", job.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump,
+        constraint = bid.status == BidStatus::Pending @ JobError::BidNotPending,
+        realloc = Bid::space(
+            proposal.as_deref().unwrap_or(&bid.proposal),
+            if milestones.is_some() { &milestones } else { &bid.milestones },
+        ),
+        realloc::payer = bidder,
+        realloc::zero = false,
+    )]
+    pub bid: Account<'info, Bid>,
+    pub system_program: Program<'info, System>,
+}
+
+// Context for withdrawing a pending bid
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct WithdrawBid<'info> {
+    pub bidder: Signer<'info>,
+    #[account(
+        seeds = [b"job", job.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
+        constraint = job.status == JobStatus::Open,
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        mut,
+        seeds = [b"bid", job.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump,
+        constraint = bid.status == BidStatus::Pending @ JobError::BidNotPending,
+    )]
+    pub bid: Account<'info, Bid>,
 }
 
 // Context for accepting a bid
 #[derive(Accounts)]
-#[instruction(job_id: u64, bid_index: u64)]
+#[instruction(job_id: u64)]
 pub struct AcceptBid<'info> {
     #[account(mut)]
     pub client: Signer<'info>,
     #[account(
         mut,
-        constraint = job.id == job_id,
+        seeds = [b"job", client.key().as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
+        constraint = job.client == client.key(),
+        constraint = job.status == JobStatus::Open,
+        realloc = Job::space(
+            &job.title,
+            &job.description,
+            &job.category,
+            &job.required_skills,
+            &bid.milestones,
+        ),
+        realloc::payer = client,
+        realloc::zero = false,
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        mut,
+        seeds = [b"bid", job.key().as_ref(), bid.bidder.as_ref()],
+        bump = bid.bump,
+        constraint = bid.status == BidStatus::Pending @ JobError::BidNotPending,
+        constraint = !bid.is_stale(&job) @ JobError::StaleBid,
+    )]
+    pub bid: Account<'info, Bid>,
+    pub system_program: Program<'info, System>,
+}
+
+// Context for rejecting a bid
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct RejectBid<'info> {
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"job", client.key().as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
         constraint = job.client == client.key(),
         constraint = job.status == JobStatus::Open,
-        constraint = bid_index < job.bids.len() as u64,
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        mut,
+        seeds = [b"bid", job.key().as_ref(), bid.bidder.as_ref()],
+        bump = bid.bump,
+        constraint = bid.status == BidStatus::Pending @ JobError::BidNotPending,
+    )]
+    pub bid: Account<'info, Bid>,
+}
+
+// Context for closing a bid that can no longer be accepted
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct CloseBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    /// CHECK: The job may already have been closed; it is deserialized in the handler when it still exists
+    #[account(address = bid.job)]
+    pub job: AccountInfo<'info>,
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [b"bid", job.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump,
+    )]
+    pub bid: Account<'info, Bid>,
+}
+
+// Context for closing a finished job
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct CloseJob<'info> {
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        close = client,
+        seeds = [b"job", client.key().as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
+        constraint = job.client == client.key(),
+        constraint = job.status == JobStatus::Completed
+            || job.status == JobStatus::Cancelled
+            || job.status == JobStatus::Expired
+            @ JobError::JobNotFinished,
+        constraint = job.escrow.is_none() @ JobError::EscrowStillOpen,
+    )]
+    pub job: Account<'info, Job>,
+}
+
+// Context for expiring an open job past its deadline
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct ExpireJob<'info> {
+    // Anyone can crank expiry
+    pub caller: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"job", job.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
+        constraint = job.status == JobStatus::Open @ JobError::JobNotOpen,
+        constraint = job.accepted_bid.is_none() @ JobError::JobNotOpen,
+        constraint = job.deadline <= Clock::get().unwrap().unix_timestamp @ JobError::JobNotExpired,
     )]
     pub job: Account<'info, Job>,
 }
@@ -162,13 +589,95 @@ pub struct CancelJob<'info> {
     pub client: Signer<'info>,
     #[account(
         mut,
-        constraint = job.id == job_id,
+        seeds = [b"job", client.key().as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
         constraint = job.client == client.key(),
         constraint = job.status == JobStatus::Open,
     )]
     pub job: Account<'info, Job>,
 }
 
+// Context for submitting the deliverable of a job without milestones
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct SubmitWork<'info> {
+    pub freelancer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"job", job.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
+        constraint = job.freelancer == Some(freelancer.key()) @ JobError::UnauthorizedFreelancer,
+        constraint = job.status == JobStatus::InProgress @ JobError::JobNotInProgress,
+        constraint = !job.has_milestones @ JobError::JobHasMilestones,
+    )]
+    pub job: Account<'info, Job>,
+}
+
+// Context for sending submitted work back for revision
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct RequestWorkRevision<'info> {
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"job", client.key().as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
+        constraint = job.client == client.key(),
+        constraint = job.status == JobStatus::InProgress @ JobError::JobNotInProgress,
+        constraint = job.work_submitted_at.is_some() @ JobError::WorkNotSubmitted,
+    )]
+    pub job: Account<'info, Job>,
+}
+
+// Context for the freelancer accepting the contract once the escrow is funded
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct AcceptContract<'info> {
+    pub freelancer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"job", job.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
+        constraint = job.freelancer == Some(freelancer.key()) @ JobError::UnauthorizedFreelancer,
+        constraint = job.status == JobStatus::InProgress @ JobError::JobNotInProgress,
+        constraint = job.escrow.is_some() @ JobError::EscrowNotCreated,
+        constraint = job.contract_accepted_at.is_none() @ JobError::ContractAlreadyAccepted,
+    )]
+    pub job: Account<'info, Job>,
+}
+
+// Context for submitting a milestone deliverable
+#[derive(Accounts)]
+#[instruction(job_id: u64, milestone_index: u8)]
+pub struct SubmitMilestone<'info> {
+    pub freelancer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"job", job.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
+        constraint = job.freelancer == Some(freelancer.key()) @ JobError::UnauthorizedFreelancer,
+        constraint = job.status == JobStatus::InProgress @ JobError::JobNotInProgress,
+        constraint = job.has_milestones @ JobError::InvalidMilestoneIndex,
+    )]
+    pub job: Account<'info, Job>,
+}
+
+// Context for sending a submitted milestone back for revision
+#[derive(Accounts)]
+#[instruction(job_id: u64, milestone_index: u8)]
+pub struct RequestMilestoneRevision<'info> {
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"job", client.key().as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
+        constraint = job.client == client.key(),
+        constraint = job.status == JobStatus::InProgress @ JobError::JobNotInProgress,
+        constraint = job.has_milestones @ JobError::InvalidMilestoneIndex,
+    )]
+    pub job: Account<'info, Job>,
+}
+
 // Function implementations
 pub fn create_job(
     ctx: Context<CreateJob>,
@@ -176,17 +685,29 @@ pub fn create_job(
     description: String,
     budget: u64,
     deadline: i64,
-    currency: String,
+    currency: Option<Pubkey>,
     category: String,
     required_skills: Vec<String>,
-    visibility: u8,
+    visibility: JobVisibility,
+    review_period: i64,
 ) -> Result<()> {
+    let client_state = &mut ctx.accounts.client_state;
     let job = &mut ctx.accounts.job;
     let client = &ctx.accounts.client;
     let clock = Clock::get()?;
 
-    // Generate job ID (can be replaced with more sophisticated ID generation)
-    let job_id = clock.unix_timestamp as u64;
+    require!(
+        (MIN_REVIEW_PERIOD..=MAX_REVIEW_PERIOD).contains(&review_period),
+        JobError::InvalidReviewPeriod
+    );
+
+    // Job IDs are sequential per client so the job address can be derived from (client, id)
+    let job_id = client_state.job_count;
+    if client_state.job_count == 0 {
+        client_state.client = client.key();
+        client_state.bump = ctx.bumps.client_state;
+    }
+    client_state.job_count = client_state.job_count.checked_add(1).ok_or(JobError::JobCounterOverflow)?;
 
     job.id = job_id;
     job.client = client.key();
@@ -199,13 +720,20 @@ pub fn create_job(
     job.required_skills = required_skills;
     job.status = JobStatus::Open;
     job.visibility = visibility;
-    job.bids = Vec::new();
+    job.accepted_bid = None;
+    job.terms_version = 0;
     job.freelancer = None;
+    job.contract_accepted_at = None;
+    job.escrow = None;
     job.created_at = clock.unix_timestamp;
     job.updated_at = clock.unix_timestamp;
     job.completed_at = None;
     job.has_milestones = false;
     job.milestones = None;
+    job.review_period = review_period;
+    job.work_submitted_at = None;
+    job.deliverable_hash = None;
+    job.bump = ctx.bumps.job;
 
     emit!(JobCreatedEvent {
         job_id,
@@ -226,67 +754,246 @@ pub fn place_bid(
     proposal: String,
     milestones: Option<Vec<Milestone>>,
 ) -> Result<()> {
-    let job = &mut ctx.accounts.job;
+    let job = &ctx.accounts.job;
+    let bid = &mut ctx.accounts.bid;
     let bidder = &ctx.accounts.bidder;
     let clock = Clock::get()?;
 
     // Validate bid
     require!(completion_time > clock.unix_timestamp, JobError::InvalidCompletionTime);
     require!(bid_amount > 0, JobError::BidAmountTooLow);
+    require!(bidder.key() != job.client, JobError::SelfBid);
+    if job.visibility.requires_invite() {
+        require!(ctx.accounts.invite.is_some(), JobError::NotInvited);
+    }
+    if let Some(milestones) = &milestones {
+        validate_milestones(milestones, clock.unix_timestamp)?;
+    }
 
     // Create bid
-    let new_bid = Bid {
+    bid.job = job.key();
+    bid.bidder = bidder.key();
+    bid.amount = bid_amount;
+    bid.completion_time = completion_time;
+    bid.proposal = proposal;
+    bid.status = BidStatus::Pending;
+    bid.milestones = milestones;
+    bid.terms_version = job.terms_version;
+    bid.created_at = clock.unix_timestamp;
+    bid.bump = ctx.bumps.bid;
+
+    emit!(BidPlacedEvent {
+        job_id,
         bidder: bidder.key(),
         amount: bid_amount,
-        proposal,
         completion_time,
-        status: BidStatus::Pending,
-        created_at: clock.unix_timestamp,
-        milestones,
-    };
+    });
+
+    Ok(())
+}
+
+pub fn update_job(
+    ctx: Context<UpdateJob>,
+    job_id: u64,
+    title: Option<String>,
+    description: Option<String>,
+    budget: Option<u64>,
+    deadline: Option<i64>,
+    category: Option<String>,
+    required_skills: Option<Vec<String>>,
+) -> Result<()> {
+    let job = &mut ctx.accounts.job;
+    let clock = Clock::get()?;
+    let mut updated_fields = Vec::new();
+    let mut terms_changed = false;
+
+    // Update fields if provided
+    if let Some(new_title) = title {
+        job.title = new_title;
+        updated_fields.push(String::from("title"));
+    }
+
+    if let Some(new_description) = description {
+        job.description = new_description;
+        updated_fields.push(String::from("description"));
+    }
+
+    if let Some(new_budget) = budget {
+        if new_budget != job.budget {
+            terms_changed = true;
+        }
+        job.budget = new_budget;
+        updated_fields.push(String::from("budget"));
+    }
+
+    if let Some(new_deadline) = deadline {
+        require!(new_deadline > clock.unix_timestamp, JobError::JobDeadlinePassed);
+        if new_deadline != job.deadline {
+            terms_changed = true;
+        }
+        job.deadline = new_deadline;
+        updated_fields.push(String::from("deadline"));
+    }
+
+    if let Some(new_category) = category {
+        job.category = new_category;
+        updated_fields.push(String::from("category"));
+    }
+
+    if let Some(new_skills) = required_skills {
+        job.required_skills = new_skills;
+        updated_fields.push(String::from("required_skills"));
+    }
+
+    // Any bids made against the old terms are now stale. The job does not count its
+    // bids, so that placing one never has to write to the job account.
+    let bids_marked_stale = terms_changed;
+    if bids_marked_stale {
+        job.terms_version = job.terms_version.checked_add(1).ok_or(JobError::JobCounterOverflow)?;
+    }
 
-    // Add bid to job
-    job.bids.push(new_bid);
     job.updated_at = clock.unix_timestamp;
 
-    emit!(BidPlacedEvent {
+    emit!(JobUpdatedEvent {
         job_id,
-        bidder: bidder.key(),
-        amount: bid_amount,
-        completion_time,
+        client: job.client,
+        updated_fields,
+        bids_marked_stale,
+    });
+
+    Ok(())
+}
+
+pub fn invite_freelancer(ctx: Context<InviteFreelancer>, job_id: u64) -> Result<()> {
+    let invite = &mut ctx.accounts.invite;
+    let freelancer = ctx.accounts.freelancer.key();
+
+    require!(freelancer != ctx.accounts.client.key(), JobError::SelfBid);
+
+    invite.job = ctx.accounts.job.key();
+    invite.freelancer = freelancer;
+    invite.invited_at = Clock::get()?.unix_timestamp;
+    invite.bump = ctx.bumps.invite;
+
+    emit!(FreelancerInvitedEvent {
+        job_id,
+        client: ctx.accounts.client.key(),
+        freelancer,
+    });
+
+    Ok(())
+}
+
+pub fn revoke_invite(ctx: Context<RevokeInvite>, job_id: u64) -> Result<()> {
+    emit!(InviteRevokedEvent {
+        job_id,
+        client: ctx.accounts.client.key(),
+        freelancer: ctx.accounts.invite.freelancer,
+    });
+
+    Ok(())
+}
+
+pub fn update_bid(
+    ctx: Context<UpdateBid>,
+    job_id: u64,
+    bid_amount: Option<u64>,
+    completion_time: Option<i64>,
+    proposal: Option<String>,
+    milestones: Option<Vec<Milestone>>,
+) -> Result<()> {
+    let bid = &mut ctx.accounts.bid;
+    let clock = Clock::get()?;
+
+    // Update fields if provided
+    if let Some(new_amount) = bid_amount {
+        require!(new_amount > 0, JobError::BidAmountTooLow);
+        bid.amount = new_amount;
+    }
+
+    if let Some(new_completion_time) = completion_time {
+        require!(new_completion_time > clock.unix_timestamp, JobError::InvalidCompletionTime);
+        bid.completion_time = new_completion_time;
+    }
+
+    if let Some(new_proposal) = proposal {
+        bid.proposal = new_proposal;
+    }
+
+    // An empty milestone plan removes the plan from the bid
+    if let Some(new_milestones) = milestones {
+        if new_milestones.is_empty() {
+            bid.milestones = None;
+        } else {
+            validate_milestones(&new_milestones, clock.unix_timestamp)?;
+            bid.milestones = Some(new_milestones);
+        }
+    }
+
+    // Amending a bid confirms it against the job's current terms
+    bid.terms_version = ctx.accounts.job.terms_version;
+
+    emit!(BidUpdatedEvent {
+        job_id,
+        bidder: bid.bidder,
+        amount: bid.amount,
+        completion_time: bid.completion_time,
+    });
+
+    Ok(())
+}
+
+pub fn withdraw_bid(ctx: Context<WithdrawBid>, job_id: u64) -> Result<()> {
+    let bid = &mut ctx.accounts.bid;
+
+    bid.status = BidStatus::Withdrawn;
+
+    emit!(BidWithdrawnEvent {
+        job_id,
+        bidder: bid.bidder,
     });
 
     Ok(())
 }
 
-pub fn accept_bid(ctx: Context<AcceptBid>, job_id: u64, bid_index: u64) -> Result<()> {
+pub fn accept_bid(ctx: Context<AcceptBid>, job_id: u64) -> Result<()> {
     let job = &mut ctx.accounts.job;
+    let bid = &mut ctx.accounts.bid;
     let client = &ctx.accounts.client;
     let clock = Clock::get()?;
 
     // Mark the selected bid as accepted
-    job.bids[bid_index as usize].status = BidStatus::Accepted;
+    bid.status = BidStatus::Accepted;
     
     // Get the freelancer pubkey from the accepted bid
-    let freelancer = job.bids[bid_index as usize].bidder;
+    let freelancer = bid.bidder;
     
     // Update job status and details
     job.status = JobStatus::InProgress;
+    job.accepted_bid = Some(bid.key());
     job.freelancer = Some(freelancer);
+    job.contract_accepted_at = None;
     job.updated_at = clock.unix_timestamp;
     
-    // If the bid has milestones, update the job milestones
-    if let Some(milestones) = &job.bids[bid_index as usize].milestones {
+    // If the bid has milestones, update the job milestones with fresh delivery state
+    if let Some(milestones) = &bid.milestones {
         job.has_milestones = true;
-        job.milestones = Some(milestones.clone());
+        job.milestones = Some(
+            milestones
+                .iter()
+                .map(|milestone| Milestone {
+                    status: MilestoneStatus::Pending,
+                    deliverable_hash: None,
+                    submitted_at: None,
+                    approved_at: None,
+                    ..milestone.clone()
+                })
+                .collect(),
+        );
     }
     
-    // Mark other bids as rejected
-    for i in 0..job.bids.len() {
-        if i != bid_index as usize {
-            job.bids[i].status = BidStatus::Rejected;
-        }
-    }
+    // Other bids stay pending; they can no longer be accepted and their
+    // bidders can close them with `close_bid`
 
     emit!(BidAcceptedEvent {
         job_id,
@@ -297,6 +1004,208 @@ pub fn accept_bid(ctx: Context<AcceptBid>, job_id: u64, bid_index: u64) -> Resul
     Ok(())
 }
 
+// Once accepted, the client can no longer take the escrow back on their own
+pub fn accept_contract(ctx: Context<AcceptContract>, job_id: u64) -> Result<()> {
+    let job = &mut ctx.accounts.job;
+    let clock = Clock::get()?;
+
+    job.contract_accepted_at = Some(clock.unix_timestamp);
+    job.updated_at = clock.unix_timestamp;
+
+    emit!(ContractAcceptedEvent {
+        job_id,
+        freelancer: ctx.accounts.freelancer.key(),
+        accepted_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn submit_work(
+    ctx: Context<SubmitWork>,
+    job_id: u64,
+    deliverable_hash: [u8; 32],
+) -> Result<()> {
+    let job = &mut ctx.accounts.job;
+    let clock = Clock::get()?;
+
+    require!(job.work_submitted_at.is_none(), JobError::WorkAlreadySubmitted);
+
+    // Delivering work accepts the contract, even without an explicit accept_contract
+    job.contract_accepted_at.get_or_insert(clock.unix_timestamp);
+    job.work_submitted_at = Some(clock.unix_timestamp);
+    job.deliverable_hash = Some(deliverable_hash);
+    job.updated_at = clock.unix_timestamp;
+
+    emit!(WorkSubmittedEvent {
+        job_id,
+        freelancer: ctx.accounts.freelancer.key(),
+        deliverable_hash,
+    });
+
+    Ok(())
+}
+
+pub fn request_work_revision(ctx: Context<RequestWorkRevision>, job_id: u64) -> Result<()> {
+    let job = &mut ctx.accounts.job;
+
+    // Clearing the submission stops the review period until the work is resubmitted
+    job.work_submitted_at = None;
+    job.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(WorkRevisionRequestedEvent {
+        job_id,
+        client: ctx.accounts.client.key(),
+    });
+
+    Ok(())
+}
+
+pub fn submit_milestone(
+    ctx: Context<SubmitMilestone>,
+    job_id: u64,
+    milestone_index: u8,
+    deliverable_hash: [u8; 32],
+) -> Result<()> {
+    let job = &mut ctx.accounts.job;
+    let clock = Clock::get()?;
+
+    let milestone = job
+        .milestones
+        .as_mut()
+        .and_then(|milestones| milestones.get_mut(milestone_index as usize))
+        .ok_or(JobError::InvalidMilestoneIndex)?;
+
+    require!(
+        milestone.status == MilestoneStatus::Pending
+            || milestone.status == MilestoneStatus::RevisionRequested,
+        JobError::MilestoneNotSubmittable
+    );
+
+    milestone.status = MilestoneStatus::Submitted;
+    milestone.deliverable_hash = Some(deliverable_hash);
+    milestone.submitted_at = Some(clock.unix_timestamp);
+    // Delivering a milestone accepts the contract, even without an explicit accept_contract
+    job.contract_accepted_at.get_or_insert(clock.unix_timestamp);
+    job.updated_at = clock.unix_timestamp;
+
+    emit!(MilestoneSubmittedEvent {
+        job_id,
+        freelancer: ctx.accounts.freelancer.key(),
+        milestone_index,
+        deliverable_hash,
+    });
+
+    Ok(())
+}
+
+pub fn request_milestone_revision(
+    ctx: Context<RequestMilestoneRevision>,
+    job_id: u64,
+    milestone_index: u8,
+) -> Result<()> {
+    let job = &mut ctx.accounts.job;
+    let clock = Clock::get()?;
+
+    let milestone = job
+        .milestones
+        .as_mut()
+        .and_then(|milestones| milestones.get_mut(milestone_index as usize))
+        .ok_or(JobError::InvalidMilestoneIndex)?;
+
+    require!(milestone.status == MilestoneStatus::Submitted, JobError::MilestoneNotSubmitted);
+
+    milestone.status = MilestoneStatus::RevisionRequested;
+    job.updated_at = clock.unix_timestamp;
+
+    emit!(MilestoneRevisionRequestedEvent {
+        job_id,
+        client: ctx.accounts.client.key(),
+        milestone_index,
+    });
+
+    Ok(())
+}
+
+pub fn reject_bid(ctx: Context<RejectBid>, job_id: u64) -> Result<()> {
+    let bid = &mut ctx.accounts.bid;
+
+    bid.status = BidStatus::Rejected;
+
+    emit!(BidRejectedEvent {
+        job_id,
+        client: ctx.accounts.client.key(),
+        bidder: bid.bidder,
+    });
+
+    Ok(())
+}
+
+pub fn close_bid(ctx: Context<CloseBid>, job_id: u64) -> Result<()> {
+    let bid = &ctx.accounts.bid;
+    let job_info = &ctx.accounts.job;
+
+    // Bids on jobs that have since been closed can always be reclaimed
+    let job_status = if job_info.owner == &crate::ID && !job_info.data_is_empty() {
+        let job = Job::try_deserialize(&mut &job_info.try_borrow_data()?[..])?;
+        require!(job.id == job_id, JobError::JobIdMismatch);
+        Some(job.status.clone())
+    } else {
+        None
+    };
+
+    let closable = match bid.status {
+        BidStatus::Rejected | BidStatus::Withdrawn => true,
+        BidStatus::Pending => job_status != Some(JobStatus::Open),
+        BidStatus::Accepted => job_status.is_none(),
+    };
+    require!(closable, JobError::BidStillActive);
+
+    emit!(BidClosedEvent {
+        job_id,
+        bidder: ctx.accounts.bidder.key(),
+    });
+
+    Ok(())
+}
+
+pub fn close_job(ctx: Context<CloseJob>, job_id: u64) -> Result<()> {
+    let job = &ctx.accounts.job;
+    let clock = Clock::get()?;
+
+    // Reviews need the job account, so keep it until the review window has passed
+    if job.status == JobStatus::Completed {
+        let completed_at = job.completed_at.unwrap_or(job.updated_at);
+        require!(
+            clock.unix_timestamp >= completed_at.saturating_add(REVIEW_WINDOW),
+            JobError::ReviewWindowOpen
+        );
+    }
+
+    emit!(JobClosedEvent {
+        job_id,
+        client: job.client,
+    });
+
+    Ok(())
+}
+
+pub fn expire_job(ctx: Context<ExpireJob>, job_id: u64) -> Result<()> {
+    let job = &mut ctx.accounts.job;
+
+    // The account stays around as Expired; the client reclaims its rent with close_job
+    job.status = JobStatus::Expired;
+    job.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(JobExpiredEvent {
+        job_id,
+        client: job.client,
+        deadline: job.deadline,
+    });
+
+    Ok(())
+}
+
 pub fn cancel_job(ctx: Context<CancelJob>, job_id: u64) -> Result<()> {
     let job = &mut ctx.accounts.job;
     let clock = Clock::get()?;
@@ -322,6 +1231,28 @@ pub struct JobCreatedEvent {
     pub deadline: i64,
 }
 
+#[event]
+pub struct JobUpdatedEvent {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub updated_fields: Vec<String>,
+    pub bids_marked_stale: bool,
+}
+
+#[event]
+pub struct FreelancerInvitedEvent {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+}
+
+#[event]
+pub struct InviteRevokedEvent {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+}
+
 #[event]
 pub struct BidPlacedEvent {
     pub job_id: u64,
@@ -330,6 +1261,20 @@ pub struct BidPlacedEvent {
     pub completion_time: i64,
 }
 
+#[event]
+pub struct BidUpdatedEvent {
+    pub job_id: u64,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub completion_time: i64,
+}
+
+#[event]
+pub struct BidWithdrawnEvent {
+    pub job_id: u64,
+    pub bidder: Pubkey,
+}
+
 #[event]
 pub struct BidAcceptedEvent {
     pub job_id: u64,
@@ -337,12 +1282,73 @@ pub struct BidAcceptedEvent {
     pub freelancer: Pubkey,
 }
 
+#[event]
+pub struct ContractAcceptedEvent {
+    pub job_id: u64,
+    pub freelancer: Pubkey,
+    pub accepted_at: i64,
+}
+
+#[event]
+pub struct WorkSubmittedEvent {
+    pub job_id: u64,
+    pub freelancer: Pubkey,
+    pub deliverable_hash: [u8; 32],
+}
+
+#[event]
+pub struct WorkRevisionRequestedEvent {
+    pub job_id: u64,
+    pub client: Pubkey,
+}
+
+#[event]
+pub struct MilestoneSubmittedEvent {
+    pub job_id: u64,
+    pub freelancer: Pubkey,
+    pub milestone_index: u8,
+    pub deliverable_hash: [u8; 32],
+}
+
+#[event]
+pub struct MilestoneRevisionRequestedEvent {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub milestone_index: u8,
+}
+
+#[event]
+pub struct BidRejectedEvent {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub bidder: Pubkey,
+}
+
+#[event]
+pub struct BidClosedEvent {
+    pub job_id: u64,
+    pub bidder: Pubkey,
+}
+
 #[event]
 pub struct JobCancelledEvent {
     pub job_id: u64,
     pub client: Pubkey,
 }
 
+#[event]
+pub struct JobClosedEvent {
+    pub job_id: u64,
+    pub client: Pubkey,
+}
+
+#[event]
+pub struct JobExpiredEvent {
+    pub job_id: u64,
+    pub client: Pubkey,
+    pub deadline: i64,
+}
+
 // Error Codes
 #[error_code]
 pub enum JobError {
@@ -360,4 +1366,52 @@ pub enum JobError {
     BidAmountTooLow,
     #[msg("Invalid bid index")]
     InvalidBidIndex,
+    #[msg("Job counter overflow")]
+    JobCounterOverflow,
+    #[msg("Bid is not pending")]
+    BidNotPending,
+    #[msg("Bid can still be accepted")]
+    BidStillActive,
+    #[msg("Clients cannot bid on their own jobs")]
+    SelfBid,
+    #[msg("Bidder has not been invited to this job")]
+    NotInvited,
+    #[msg("Job terms changed after this bid was placed; update the bid first")]
+    StaleBid,
+    #[msg("Job deadline has not passed yet")]
+    JobNotExpired,
+    #[msg("Job is not completed or cancelled")]
+    JobNotFinished,
+    #[msg("Job escrow must be closed first")]
+    EscrowStillOpen,
+    #[msg("Review window is still open")]
+    ReviewWindowOpen,
+    #[msg("Only the assigned freelancer can do this")]
+    UnauthorizedFreelancer,
+    #[msg("Job is not in progress")]
+    JobNotInProgress,
+    #[msg("Invalid milestone index")]
+    InvalidMilestoneIndex,
+    #[msg("Milestone has already been submitted or approved")]
+    MilestoneNotSubmittable,
+    #[msg("Milestone has not been submitted")]
+    MilestoneNotSubmitted,
+    #[msg("Review period is outside the allowed range")]
+    InvalidReviewPeriod,
+    #[msg("Jobs with milestones are submitted per milestone")]
+    JobHasMilestones,
+    #[msg("Work has not been submitted")]
+    WorkNotSubmitted,
+    #[msg("Work has already been submitted")]
+    WorkAlreadySubmitted,
+    #[msg("Milestone plan must have between 1 and 10 milestones")]
+    InvalidMilestoneCount,
+    #[msg("Milestone amounts must be positive and add up to 10000 basis points")]
+    InvalidMilestoneAmount,
+    #[msg("Milestone deadlines must be in the future and in increasing order")]
+    InvalidMilestoneDeadline,
+    #[msg("Escrow has not been created for this job")]
+    EscrowNotCreated,
+    #[msg("Contract has already been accepted")]
+    ContractAlreadyAccepted,
 } 
\ No newline at end of file
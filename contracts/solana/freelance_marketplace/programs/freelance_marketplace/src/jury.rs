@@ -0,0 +1,883 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use crate::config::Config;
+// Glob import so the nested DisputePayout accounts bring their generated modules along
+use crate::dispute::*;
+
+pub const JURY_SIZE: usize = 5;
+pub const MAX_POOL_JURORS: usize = 100;
+pub const COMMIT_PERIOD: i64 = 3 * 24 * 60 * 60; // 3 days
+pub const REVEAL_PERIOD: i64 = 2 * 24 * 60 * 60; // 2 days
+pub const UNBONDING_PERIOD: i64 = 14 * 24 * 60 * 60; // 14 days, outlasts any jury a juror sits on
+
+// Every juror currently eligible for selection. Jurors are drawn weighted by
+// stake, so filling the pool with minimum stakes does not buy control of juries.
+#[account]
+pub struct JurorPool {
+    pub jurors: Vec<PoolJuror>,
+    pub bump: u8,
+}
+
+impl JurorPool {
+    pub const SPACE: usize = 8 + // discriminator
+        4 + (PoolJuror::SPACE * MAX_POOL_JURORS) + // jurors
+        1; // bump
+
+    pub fn remove(&mut self, authority: &Pubkey) {
+        self.jurors.retain(|juror| juror.authority != *authority);
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PoolJuror {
+    pub authority: Pubkey,
+    pub stake: u64, // Selection weight; mirrors the juror's stake
+}
+
+impl PoolJuror {
+    pub const SPACE: usize = 32 + // authority
+        8; // stake
+}
+
+// A juror's stake. The staked lamports are held by this account itself.
+#[account]
+pub struct Juror {
+    pub authority: Pubkey,
+    pub stake: u64,
+    pub unbonding_at: Option<i64>, // Stake can be withdrawn after this, once the juror has left the pool
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+impl Juror {
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // authority
+        8 + // stake
+        1 + 8 + // unbonding_at
+        8 + // registered_at
+        1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct JuryVerdict {
+    pub resolution_type: ResolutionType,
+    pub freelancer_bps: u16,
+}
+
+impl JuryVerdict {
+    // Votes only differ in how the escrow is paid out, so the outcome is described
+    // by the split alone, e.g. a full split counts as a release to the freelancer
+    pub fn from_freelancer_bps(freelancer_bps: u16) -> Self {
+        let resolution_type = match freelancer_bps {
+            TOTAL_SPLIT_BPS => ResolutionType::ReleaseToFreelancer,
+            0 => ResolutionType::RefundToClient,
+            _ => ResolutionType::Split,
+        };
+
+        Self {
+            resolution_type,
+            freelancer_bps,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct JurorVote {
+    pub juror: Pubkey,
+    pub commitment: Option<[u8; 32]>,
+    pub verdict: Option<JuryVerdict>,
+    pub settled: bool, // Fee claimed or stake slashed
+}
+
+impl JurorVote {
+    pub const SPACE: usize = 32 + // juror
+        1 + 32 + // commitment
+        1 + 1 + 2 + // verdict
+        1; // settled
+}
+
+// Jurors assigned to a dispute and their commit-reveal votes. The jury fee is
+// held by this account until the majority jurors claim it.
+#[account]
+pub struct Jury {
+    pub dispute: Pubkey,
+    pub requester: Pubkey,
+    pub votes: Vec<JurorVote>,
+    pub commit_deadline: i64,
+    pub reveal_deadline: i64,
+    pub fee_pool: u64,
+    pub reward_per_juror: u64,
+    pub outcome: Option<JuryVerdict>,
+    pub finalized: bool,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Jury {
+    pub const SPACE: usize = 8 + // discriminator
+        32 + // dispute
+        32 + // requester
+        4 + (JurorVote::SPACE * JURY_SIZE) + // votes
+        8 + // commit_deadline
+        8 + // reveal_deadline
+        8 + // fee_pool
+        8 + // reward_per_juror
+        1 + 1 + 2 + // outcome
+        1 + // finalized
+        8 + // created_at
+        1; // bump
+
+    // Jurors who share the fee: those who voted for the outcome's split, or
+    // everyone who revealed when there was no majority
+    pub fn is_rewarded(&self, vote: &JurorVote) -> bool {
+        match (vote.verdict, self.outcome) {
+            (Some(verdict), Some(outcome)) => verdict.freelancer_bps == outcome.freelancer_bps,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    pub fn vote_mut(&mut self, juror: &Pubkey) -> Result<&mut JurorVote> {
+        self.votes
+            .iter_mut()
+            .find(|vote| vote.juror == *juror)
+            .ok_or_else(|| error!(JuryError::NotAJuror))
+    }
+}
+
+// Hash a juror commits to before revealing their verdict
+pub fn vote_commitment(juror: &Pubkey, resolution_type: ResolutionType, split_bps: u16, salt: &[u8; 32]) -> [u8; 32] {
+    hashv(&[
+        juror.as_ref(),
+        &[resolution_type as u8],
+        &split_bps.to_le_bytes(),
+        salt,
+    ])
+    .to_bytes()
+}
+
+// Context for the admin creating the juror pool
+#[derive(Accounts)]
+pub struct InitializeJurorPool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ JuryError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        space = JurorPool::SPACE,
+        seeds = [b"juror_pool"],
+        bump,
+    )]
+    pub juror_pool: Account<'info, JurorPool>,
+    pub system_program: Program<'info, System>,
+}
+
+// Context for staking into the juror pool
+#[derive(Accounts)]
+pub struct RegisterJuror<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = Juror::SPACE,
+        seeds = [b"juror", authority.key().as_ref()],
+        bump,
+    )]
+    pub juror: Account<'info, Juror>,
+    #[account(mut, seeds = [b"juror_pool"], bump = juror_pool.bump)]
+    pub juror_pool: Account<'info, JurorPool>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+// Context for leaving the juror pool
+#[derive(Accounts)]
+pub struct UnregisterJuror<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"juror", authority.key().as_ref()],
+        bump = juror.bump,
+        has_one = authority @ JuryError::Unauthorized,
+        constraint = juror.unbonding_at.is_none() @ JuryError::AlreadyUnbonding,
+    )]
+    pub juror: Account<'info, Juror>,
+    #[account(mut, seeds = [b"juror_pool"], bump = juror_pool.bump)]
+    pub juror_pool: Account<'info, JurorPool>,
+}
+
+// Context for the admin removing a juror from the pool
+#[derive(Accounts)]
+pub struct RemoveJuror<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ JuryError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"juror", juror.authority.as_ref()],
+        bump = juror.bump,
+        constraint = juror.unbonding_at.is_none() @ JuryError::AlreadyUnbonding,
+    )]
+    pub juror: Account<'info, Juror>,
+    #[account(mut, seeds = [b"juror_pool"], bump = juror_pool.bump)]
+    pub juror_pool: Account<'info, JurorPool>,
+}
+
+// Context for withdrawing stake once unbonding has finished
+#[derive(Accounts)]
+pub struct WithdrawJurorStake<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"juror", authority.key().as_ref()],
+        bump = juror.bump,
+        has_one = authority @ JuryError::Unauthorized,
+    )]
+    pub juror: Account<'info, Juror>,
+}
+
+// Context for a party handing their dispute to a jury
+#[derive(Accounts)]
+pub struct RequestJury<'info> {
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.job.as_ref(), &dispute.scope().to_le_bytes()],
+        bump = dispute.bump,
+        constraint = !dispute.is_resolved @ JuryError::DisputeResolved,
+        constraint = dispute.jury.is_none() @ JuryError::JuryAlreadyAssigned,
+        constraint = dispute.appeal_deadline.is_none()
+            && dispute.appeal.is_none() @ JuryError::RulingPending,
+        constraint = requester.key() == dispute.client
+            || requester.key() == dispute.freelancer @ JuryError::NotAParty,
+    )]
+    pub dispute: Account<'info, Dispute>,
+    #[account(
+        init,
+        payer = requester,
+        space = Jury::SPACE,
+        seeds = [b"jury", dispute.key().as_ref()],
+        bump,
+    )]
+    pub jury: Account<'info, Jury>,
+    #[account(seeds = [b"juror_pool"], bump = juror_pool.bump)]
+    pub juror_pool: Account<'info, JurorPool>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: SlotHashes sysvar, only read as a source of randomness
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Context for a juror committing or revealing a vote
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    pub juror: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"jury", jury.dispute.as_ref()],
+        bump = jury.bump,
+        constraint = !jury.finalized @ JuryError::AlreadyFinalized,
+    )]
+    pub jury: Account<'info, Jury>,
+}
+
+// Context for tallying the votes and paying out the majority verdict
+#[derive(Accounts)]
+pub struct FinalizeJury<'info> {
+    pub payout: DisputePayout<'info>,
+    #[account(
+        mut,
+        seeds = [b"jury", payout.dispute.key().as_ref()],
+        bump = jury.bump,
+        constraint = !jury.finalized @ JuryError::AlreadyFinalized,
+    )]
+    pub jury: Account<'info, Jury>,
+    /// CHECK: Receives the jury fee back if no juror revealed
+    #[account(mut, address = jury.requester @ JuryError::Unauthorized)]
+    pub requester: AccountInfo<'info>,
+}
+
+// Context for a majority juror claiming their share of the jury fee
+#[derive(Accounts)]
+pub struct ClaimJurorFee<'info> {
+    #[account(mut)]
+    pub juror: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"jury", jury.dispute.as_ref()],
+        bump = jury.bump,
+        constraint = jury.finalized @ JuryError::NotFinalized,
+    )]
+    pub jury: Account<'info, Jury>,
+}
+
+// Context for slashing a juror who never revealed their vote
+#[derive(Accounts)]
+pub struct SlashJuror<'info> {
+    pub caller: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"jury", jury.dispute.as_ref()],
+        bump = jury.bump,
+    )]
+    pub jury: Account<'info, Jury>,
+    #[account(
+        mut,
+        seeds = [b"juror", juror.authority.as_ref()],
+        bump = juror.bump,
+    )]
+    pub juror: Account<'info, Juror>,
+    #[account(mut, seeds = [b"juror_pool"], bump = juror_pool.bump)]
+    pub juror_pool: Account<'info, JurorPool>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: This is safe as we validate it matches the config treasury
+    #[account(mut, address = config.treasury @ JuryError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+}
+
+#[event]
+pub struct JurorRegistered {
+    pub juror: Pubkey,
+    pub stake: u64,
+}
+
+#[event]
+pub struct JurorUnregistered {
+    pub juror: Pubkey,
+    pub unbonding_at: i64,
+}
+
+#[event]
+pub struct JurorRemoved {
+    pub juror: Pubkey,
+    pub unbonding_at: i64,
+}
+
+#[event]
+pub struct JuryAssigned {
+    pub dispute: Pubkey,
+    pub jury: Pubkey,
+    pub jurors: Vec<Pubkey>,
+    pub commit_deadline: i64,
+    pub reveal_deadline: i64,
+}
+
+#[event]
+pub struct VoteCommitted {
+    pub jury: Pubkey,
+    pub juror: Pubkey,
+}
+
+#[event]
+pub struct VoteRevealed {
+    pub jury: Pubkey,
+    pub juror: Pubkey,
+    pub resolution_type: ResolutionType,
+    pub freelancer_bps: u16,
+}
+
+#[event]
+pub struct JuryFinalized {
+    pub dispute: Pubkey,
+    pub jury: Pubkey,
+    pub outcome: Option<JuryVerdict>,
+    pub revealed_votes: u8,
+    pub freelancer_amount: u64,
+    pub client_amount: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct JurorFeeClaimed {
+    pub jury: Pubkey,
+    pub juror: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct JurorSlashed {
+    pub jury: Pubkey,
+    pub juror: Pubkey,
+    pub amount: u64,
+    pub remaining_stake: u64,
+}
+
+pub fn initialize_juror_pool(ctx: Context<InitializeJurorPool>) -> Result<()> {
+    let juror_pool = &mut ctx.accounts.juror_pool;
+
+    juror_pool.jurors = Vec::new();
+    juror_pool.bump = ctx.bumps.juror_pool;
+
+    Ok(())
+}
+
+pub fn register_juror(ctx: Context<RegisterJuror>, stake: u64) -> Result<()> {
+    let juror = &mut ctx.accounts.juror;
+    let juror_pool = &mut ctx.accounts.juror_pool;
+    let authority = &ctx.accounts.authority;
+
+    require!(stake >= ctx.accounts.config.min_juror_stake, JuryError::StakeTooLow);
+    require!(juror_pool.jurors.len() < MAX_POOL_JURORS, JuryError::PoolFull);
+
+    // Lock the stake in the juror account
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: authority.to_account_info(),
+                to: juror.to_account_info(),
+            },
+        ),
+        stake,
+    )?;
+
+    juror.authority = authority.key();
+    juror.stake = stake;
+    juror.unbonding_at = None;
+    juror.registered_at = Clock::get()?.unix_timestamp;
+    juror.bump = ctx.bumps.juror;
+
+    juror_pool.jurors.push(PoolJuror {
+        authority: authority.key(),
+        stake,
+    });
+
+    emit!(JurorRegistered {
+        juror: authority.key(),
+        stake,
+    });
+
+    Ok(())
+}
+
+// Leaves the pool straight away, but the stake stays locked for the unbonding
+// period so a juror cannot dodge slashing on a jury they already sit on
+pub fn unregister_juror(ctx: Context<UnregisterJuror>) -> Result<()> {
+    let juror = &mut ctx.accounts.juror;
+    let unbonding_at = start_unbonding(juror, &mut ctx.accounts.juror_pool)?;
+
+    emit!(JurorUnregistered {
+        juror: juror.authority,
+        unbonding_at,
+    });
+
+    Ok(())
+}
+
+// Lets the admin evict a juror, e.g. one of many accounts run by the same
+// person. The stake unbonds as if the juror had left on their own.
+pub fn remove_juror(ctx: Context<RemoveJuror>) -> Result<()> {
+    let juror = &mut ctx.accounts.juror;
+    let unbonding_at = start_unbonding(juror, &mut ctx.accounts.juror_pool)?;
+
+    emit!(JurorRemoved {
+        juror: juror.authority,
+        unbonding_at,
+    });
+
+    Ok(())
+}
+
+fn start_unbonding(juror: &mut Juror, juror_pool: &mut JurorPool) -> Result<i64> {
+    let unbonding_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(UNBONDING_PERIOD)
+        .ok_or(JuryError::MathOverflow)?;
+
+    juror_pool.remove(&juror.authority);
+    juror.unbonding_at = Some(unbonding_at);
+
+    Ok(unbonding_at)
+}
+
+pub fn withdraw_juror_stake(ctx: Context<WithdrawJurorStake>) -> Result<()> {
+    let juror = &ctx.accounts.juror;
+    let unbonding_at = juror.unbonding_at.ok_or(JuryError::NotUnbonding)?;
+
+    require!(Clock::get()?.unix_timestamp >= unbonding_at, JuryError::StillUnbonding);
+
+    // Closing the account returns the stake and its rent to the authority
+    Ok(())
+}
+
+pub fn request_jury(ctx: Context<RequestJury>) -> Result<()> {
+    let jury = &mut ctx.accounts.jury;
+    let dispute = &mut ctx.accounts.dispute;
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    // Draw the jurors from the most recent slot hash, mixed with the dispute key
+    let seed = {
+        let slot_hashes = ctx.accounts.slot_hashes.try_borrow_data()?;
+        // Layout: u64 entry count, then (u64 slot, [u8; 32] hash) entries, newest first
+        require!(slot_hashes.len() >= 48, JuryError::RandomnessUnavailable);
+        hashv(&[&slot_hashes[16..48], dispute.key().as_ref()]).to_bytes()
+    };
+    let jurors = select_jurors(
+        &ctx.accounts.juror_pool.jurors,
+        &[dispute.client, dispute.freelancer],
+        seed,
+    )?;
+
+    // The requester pays every juror's fee up front
+    let fee_pool = config
+        .juror_fee
+        .checked_mul(JURY_SIZE as u64)
+        .ok_or(JuryError::MathOverflow)?;
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.requester.to_account_info(),
+                to: jury.to_account_info(),
+            },
+        ),
+        fee_pool,
+    )?;
+
+    jury.dispute = dispute.key();
+    jury.requester = ctx.accounts.requester.key();
+    jury.votes = jurors
+        .iter()
+        .map(|juror| JurorVote {
+            juror: *juror,
+            commitment: None,
+            verdict: None,
+            settled: false,
+        })
+        .collect();
+    jury.commit_deadline = clock.unix_timestamp.checked_add(COMMIT_PERIOD).ok_or(JuryError::MathOverflow)?;
+    jury.reveal_deadline = jury.commit_deadline.checked_add(REVEAL_PERIOD).ok_or(JuryError::MathOverflow)?;
+    jury.fee_pool = fee_pool;
+    jury.reward_per_juror = 0;
+    jury.outcome = None;
+    jury.finalized = false;
+    jury.created_at = clock.unix_timestamp;
+    jury.bump = ctx.bumps.jury;
+
+    dispute.jury = Some(jury.key());
+
+    emit!(JuryAssigned {
+        dispute: dispute.key(),
+        jury: jury.key(),
+        jurors,
+        commit_deadline: jury.commit_deadline,
+        reveal_deadline: jury.reveal_deadline,
+    });
+
+    Ok(())
+}
+
+// Draws JURY_SIZE distinct jurors without replacement, each draw weighted by stake
+fn select_jurors(pool: &[PoolJuror], excluded: &[Pubkey], seed: [u8; 32]) -> Result<Vec<Pubkey>> {
+    let mut candidates: Vec<PoolJuror> = pool
+        .iter()
+        .filter(|juror| !excluded.contains(&juror.authority))
+        .copied()
+        .collect();
+    require!(candidates.len() >= JURY_SIZE, JuryError::NotEnoughJurors);
+
+    let mut seed = seed;
+    let mut jurors = Vec::with_capacity(JURY_SIZE);
+    for _ in 0..JURY_SIZE {
+        let total_stake: u128 = candidates.iter().map(|juror| juror.stake as u128).sum();
+        require!(total_stake > 0, JuryError::NotEnoughJurors);
+
+        let mut roll = [0u8; 16];
+        roll.copy_from_slice(&seed[..16]);
+        let mut target = u128::from_le_bytes(roll) % total_stake;
+
+        let mut pick = candidates.len() - 1;
+        for (index, juror) in candidates.iter().enumerate() {
+            if target < juror.stake as u128 {
+                pick = index;
+                break;
+            }
+            target -= juror.stake as u128;
+        }
+
+        jurors.push(candidates.swap_remove(pick).authority);
+        seed = hashv(&[&seed]).to_bytes();
+    }
+
+    Ok(jurors)
+}
+
+pub fn commit_vote(ctx: Context<CastVote>, commitment: [u8; 32]) -> Result<()> {
+    let jury = &mut ctx.accounts.jury;
+    let juror = ctx.accounts.juror.key();
+
+    require!(Clock::get()?.unix_timestamp < jury.commit_deadline, JuryError::CommitPeriodOver);
+
+    // A juror may replace their commitment until the commit period ends
+    jury.vote_mut(&juror)?.commitment = Some(commitment);
+
+    emit!(VoteCommitted {
+        jury: jury.key(),
+        juror,
+    });
+
+    Ok(())
+}
+
+pub fn reveal_vote(
+    ctx: Context<CastVote>,
+    resolution_type: ResolutionType,
+    split_bps: u16,
+    salt: [u8; 32],
+) -> Result<()> {
+    let jury = &mut ctx.accounts.jury;
+    let juror = ctx.accounts.juror.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(now >= jury.commit_deadline, JuryError::CommitPeriodActive);
+    require!(now < jury.reveal_deadline, JuryError::RevealPeriodOver);
+
+    let freelancer_bps = resolution_type.freelancer_bps(split_bps)?;
+    let vote = jury.vote_mut(&juror)?;
+    let commitment = vote.commitment.ok_or(JuryError::NoCommitment)?;
+
+    require!(vote.verdict.is_none(), JuryError::AlreadyRevealed);
+    require!(
+        vote_commitment(&juror, resolution_type, split_bps, &salt) == commitment,
+        JuryError::CommitmentMismatch
+    );
+
+    vote.verdict = Some(JuryVerdict {
+        resolution_type,
+        freelancer_bps,
+    });
+
+    emit!(VoteRevealed {
+        jury: jury.key(),
+        juror,
+        resolution_type,
+        freelancer_bps,
+    });
+
+    Ok(())
+}
+
+pub fn finalize_jury(ctx: Context<FinalizeJury>) -> Result<()> {
+    let clock = Clock::get()?;
+    let jury = &mut ctx.accounts.jury;
+
+    let revealed: Vec<JuryVerdict> = jury.votes.iter().filter_map(|vote| vote.verdict).collect();
+    require!(
+        clock.unix_timestamp >= jury.reveal_deadline || revealed.len() == jury.votes.len(),
+        JuryError::RevealPeriodActive
+    );
+
+    // The outcome needs a strict majority of the revealed votes for the same split
+    let outcome = revealed
+        .iter()
+        .map(|verdict| verdict.freelancer_bps)
+        .find(|bps| revealed.iter().filter(|other| other.freelancer_bps == *bps).count() * 2 > revealed.len())
+        .map(JuryVerdict::from_freelancer_bps);
+
+    // Jurors in the majority share the fee. Without a majority, everyone who
+    // revealed did their part and shares it.
+    jury.outcome = outcome;
+    jury.finalized = true;
+    let winners = jury.votes.iter().filter(|vote| jury.is_rewarded(vote)).count() as u64;
+
+    if let Some(reward_per_juror) = jury.fee_pool.checked_div(winners) {
+        jury.reward_per_juror = reward_per_juror;
+    } else {
+        // Nobody revealed; give the fee back to the requester
+        let fee_pool = jury.fee_pool;
+        **jury.to_account_info().try_borrow_mut_lamports()? -= fee_pool;
+        **ctx.accounts.requester.try_borrow_mut_lamports()? += fee_pool;
+        jury.fee_pool = 0;
+    }
+
+    let (freelancer_amount, client_amount, fee) = match outcome {
+        Some(verdict) => {
+            let settlement = ctx.accounts.payout.settle(
+                ResolutionDetails {
+                    resolved_by: jury.key(),
+                    resolution_type: verdict.resolution_type,
+                    split_bps: if verdict.resolution_type == ResolutionType::Split {
+                        Some(verdict.freelancer_bps)
+                    } else {
+                        None
+                    },
+                    resolution_note: String::from("Decided by jury vote"),
+                    resolved_at: clock.unix_timestamp,
+                },
+                clock.unix_timestamp,
+            )?;
+
+            (settlement.freelancer_amount, settlement.client_amount, settlement.fee)
+        }
+        None => {
+            // No majority: hand the dispute back to the arbiters
+            ctx.accounts.payout.dispute.jury = None;
+            (0, 0, 0)
+        }
+    };
+
+    emit!(JuryFinalized {
+        dispute: ctx.accounts.payout.dispute.key(),
+        jury: jury.key(),
+        outcome,
+        revealed_votes: revealed.len() as u8,
+        freelancer_amount,
+        client_amount,
+        fee,
+    });
+
+    Ok(())
+}
+
+pub fn claim_juror_fee(ctx: Context<ClaimJurorFee>) -> Result<()> {
+    let jury = &mut ctx.accounts.jury;
+    let juror = ctx.accounts.juror.key();
+
+    let vote = jury.vote_mut(&juror)?.clone();
+    require!(jury.is_rewarded(&vote), JuryError::NotInMajority);
+    require!(!vote.settled, JuryError::AlreadySettled);
+    jury.vote_mut(&juror)?.settled = true;
+
+    // The last juror to claim also takes the rounding remainder, so the fee pool
+    // ends up fully paid out
+    let unclaimed = jury
+        .votes
+        .iter()
+        .filter(|vote| !vote.settled && jury.is_rewarded(vote))
+        .count();
+    let amount = if unclaimed == 0 {
+        jury.fee_pool
+    } else {
+        jury.reward_per_juror
+    };
+    jury.fee_pool = jury.fee_pool.checked_sub(amount).ok_or(JuryError::MathOverflow)?;
+
+    **jury.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.juror.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    emit!(JurorFeeClaimed {
+        jury: jury.key(),
+        juror,
+        amount,
+    });
+
+    Ok(())
+}
+
+pub fn slash_juror(ctx: Context<SlashJuror>) -> Result<()> {
+    let jury = &mut ctx.accounts.jury;
+    let juror = &mut ctx.accounts.juror;
+    let config = &ctx.accounts.config;
+
+    require!(Clock::get()?.unix_timestamp >= jury.reveal_deadline, JuryError::RevealPeriodActive);
+
+    let vote = jury.vote_mut(&juror.authority)?;
+    require!(vote.verdict.is_none(), JuryError::AlreadyRevealed);
+    require!(!vote.settled, JuryError::AlreadySettled);
+    vote.settled = true;
+
+    let amount = (juror.stake as u128)
+        .checked_mul(config.juror_slash_bps as u128)
+        .ok_or(JuryError::MathOverflow)?
+        / 10_000;
+    let amount = amount as u64;
+
+    juror.stake = juror.stake.checked_sub(amount).ok_or(JuryError::MathOverflow)?;
+    **juror.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.treasury.try_borrow_mut_lamports()? += amount;
+
+    // Jurors whose stake falls below the minimum drop out of the pool; the rest
+    // keep a selection weight that matches their reduced stake
+    let juror_pool = &mut ctx.accounts.juror_pool;
+    if juror.stake < config.min_juror_stake {
+        juror_pool.remove(&juror.authority);
+    } else if let Some(entry) = juror_pool.jurors.iter_mut().find(|entry| entry.authority == juror.authority) {
+        entry.stake = juror.stake;
+    }
+
+    emit!(JurorSlashed {
+        jury: jury.key(),
+        juror: juror.authority,
+        amount,
+        remaining_stake: juror.stake,
+    });
+
+    Ok(())
+}
+
+#[error_code]
+pub enum JuryError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Stake is below the minimum juror stake")]
+    StakeTooLow,
+    #[msg("Juror pool is full")]
+    PoolFull,
+    #[msg("Not enough eligible jurors in the pool")]
+    NotEnoughJurors,
+    #[msg("Juror is already unbonding")]
+    AlreadyUnbonding,
+    #[msg("Juror has not left the pool")]
+    NotUnbonding,
+    #[msg("Juror stake is still unbonding")]
+    StillUnbonding,
+    #[msg("Dispute already resolved")]
+    DisputeResolved,
+    #[msg("Dispute already has a jury")]
+    JuryAlreadyAssigned,
+    #[msg("Only the client or freelancer can request a jury")]
+    NotAParty,
+    #[msg("Slot hashes are unavailable")]
+    RandomnessUnavailable,
+    #[msg("Signer is not on this jury")]
+    NotAJuror,
+    #[msg("Commit period is over")]
+    CommitPeriodOver,
+    #[msg("Commit period is still running")]
+    CommitPeriodActive,
+    #[msg("Reveal period is over")]
+    RevealPeriodOver,
+    #[msg("Reveal period is still running")]
+    RevealPeriodActive,
+    #[msg("No vote was committed")]
+    NoCommitment,
+    #[msg("Vote already revealed")]
+    AlreadyRevealed,
+    #[msg("Revealed vote does not match the commitment")]
+    CommitmentMismatch,
+    #[msg("Dispute already has an arbiter ruling")]
+    RulingPending,
+    #[msg("Jury already finalized")]
+    AlreadyFinalized,
+    #[msg("Jury not finalized yet")]
+    NotFinalized,
+    #[msg("Juror did not vote with the majority")]
+    NotInMajority,
+    #[msg("Juror fee already claimed or stake already slashed")]
+    AlreadySettled,
+    #[msg("Treasury does not match the marketplace config")]
+    InvalidTreasury,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
\ No newline at end of file
@@ -1,8 +1,8 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token;
 use anchor_spl::associated_token;
-use job::{Job, JobStatus, CancelJob, CreateJob, PlaceBid, AcceptBid, Milestone};
-use escrow::{Escrow, CreateEscrow, ReleaseEscrow, ReleaseMilestonePayment, RefundEscrow, MilestonePayment};
+use job::{Job, JobStatus, JobVisibility, AcceptContract, CancelJob, CloseJob, ExpireJob, CreateJob, UpdateJob, InviteFreelancer, RevokeInvite, PlaceBid, AcceptBid, UpdateBid, WithdrawBid, RejectBid, CloseBid, SubmitWork, RequestWorkRevision, SubmitMilestone, RequestMilestoneRevision, Milestone};
+use escrow::{Escrow, CreateEscrow, FundEscrow, ReleaseEscrow, ClaimAfterTimeout, RefundEscrow, ProposeCancellation, AcceptCancellation, CloseEscrow, MilestonePayment};
 use dispute::{Dispute, InitiateDispute, ResolveDispute};
 use portfolio::{Portfolio, CreatePortfolio, UpdatePortfolio};
 use review::{Review, CreateReview};
@@ -10,23 +10,79 @@ use profile::{Profile, CreateProfile, UpdateProfile};
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
+pub mod config;
 pub mod job;
 pub mod escrow;
 pub mod profile;
 pub mod dispute;
+pub mod jury;
 pub mod portfolio;
 pub mod review;
 
+use config::*;
 use job::*;
 use escrow::*;
 use profile::*;
 use dispute::*;
+use jury::*;
 use portfolio::*;
 
 #[program]
 pub mod freelance_marketplace {
     use super::*;
 
+    // Config Module Functions
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        treasury: Pubkey,
+        fee_bps: u16
+    ) -> Result<()> {
+        config::initialize_config(ctx, treasury, fee_bps)
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        treasury: Option<Pubkey>,
+        fee_bps: Option<u16>
+    ) -> Result<()> {
+        config::update_config(ctx, treasury, fee_bps)
+    }
+
+    pub fn update_jury_config(
+        ctx: Context<UpdateConfig>,
+        min_juror_stake: Option<u64>,
+        juror_fee: Option<u64>,
+        juror_slash_bps: Option<u16>
+    ) -> Result<()> {
+        config::update_jury_config(ctx, min_juror_stake, juror_fee, juror_slash_bps)
+    }
+
+    pub fn update_dispute_config(
+        ctx: Context<UpdateConfig>,
+        appeal_window: Option<i64>,
+        appeal_bond: Option<u64>,
+        dispute_bond_bps: Option<u16>,
+        bond_to_counterparty: Option<bool>
+    ) -> Result<()> {
+        config::update_dispute_config(ctx, appeal_window, appeal_bond, dispute_bond_bps, bond_to_counterparty)
+    }
+
+    pub fn add_arbiter(ctx: Context<UpdateConfig>, arbiter: Pubkey) -> Result<()> {
+        config::add_arbiter(ctx, arbiter)
+    }
+
+    pub fn remove_arbiter(ctx: Context<UpdateConfig>, arbiter: Pubkey) -> Result<()> {
+        config::remove_arbiter(ctx, arbiter)
+    }
+
+    pub fn propose_admin_transfer(ctx: Context<UpdateConfig>, new_admin: Pubkey) -> Result<()> {
+        config::propose_admin_transfer(ctx, new_admin)
+    }
+
+    pub fn accept_admin_transfer(ctx: Context<AcceptAdminTransfer>) -> Result<()> {
+        config::accept_admin_transfer(ctx)
+    }
+
     // Job Module Functions
     pub fn create_job(
         ctx: Context<CreateJob>,
@@ -34,10 +90,11 @@ pub mod freelance_marketplace {
         description: String,
         budget: u64,
         deadline: i64,
+        currency: Option<Pubkey>,
         category: String,
         skills: Vec<String>,
-        has_milestones: bool,
-        milestones: Option<Vec<Milestone>>
+        visibility: JobVisibility,
+        review_period: i64
     ) -> Result<()> {
         job::create_job(
             ctx, 
@@ -45,30 +102,89 @@ pub mod freelance_marketplace {
             description, 
             budget, 
             deadline, 
+            currency,
             category, 
             skills,
-            has_milestones,
-            milestones
+            visibility,
+            review_period
         )
     }
 
+    pub fn update_job(
+        ctx: Context<UpdateJob>,
+        job_id: u64,
+        title: Option<String>,
+        description: Option<String>,
+        budget: Option<u64>,
+        deadline: Option<i64>,
+        category: Option<String>,
+        skills: Option<Vec<String>>
+    ) -> Result<()> {
+        job::update_job(ctx, job_id, title, description, budget, deadline, category, skills)
+    }
+
+    pub fn invite_freelancer(
+        ctx: Context<InviteFreelancer>,
+        job_id: u64
+    ) -> Result<()> {
+        job::invite_freelancer(ctx, job_id)
+    }
+
+    pub fn revoke_invite(
+        ctx: Context<RevokeInvite>,
+        job_id: u64
+    ) -> Result<()> {
+        job::revoke_invite(ctx, job_id)
+    }
+
     pub fn place_bid(
         ctx: Context<PlaceBid>,
         job_id: u64,
         amount: u64,
-        description: String,
-        deadline: i64,
+        completion_time: i64,
+        proposal: String,
         milestones: Option<Vec<Milestone>>
     ) -> Result<()> {
-        job::place_bid(ctx, job_id, amount, description, deadline, milestones)
+        job::place_bid(ctx, job_id, amount, completion_time, proposal, milestones)
+    }
+
+    pub fn update_bid(
+        ctx: Context<UpdateBid>,
+        job_id: u64,
+        amount: Option<u64>,
+        completion_time: Option<i64>,
+        proposal: Option<String>,
+        milestones: Option<Vec<Milestone>>
+    ) -> Result<()> {
+        job::update_bid(ctx, job_id, amount, completion_time, proposal, milestones)
+    }
+
+    pub fn withdraw_bid(
+        ctx: Context<WithdrawBid>,
+        job_id: u64
+    ) -> Result<()> {
+        job::withdraw_bid(ctx, job_id)
     }
 
     pub fn accept_bid(
         ctx: Context<AcceptBid>,
-        job_id: u64,
-        bid_index: u8
+        job_id: u64
     ) -> Result<()> {
-        job::accept_bid(ctx, job_id, bid_index)
+        job::accept_bid(ctx, job_id)
+    }
+
+    pub fn reject_bid(
+        ctx: Context<RejectBid>,
+        job_id: u64
+    ) -> Result<()> {
+        job::reject_bid(ctx, job_id)
+    }
+
+    pub fn close_bid(
+        ctx: Context<CloseBid>,
+        job_id: u64
+    ) -> Result<()> {
+        job::close_bid(ctx, job_id)
     }
 
     pub fn cancel_job(
@@ -78,13 +194,75 @@ pub mod freelance_marketplace {
         job::cancel_job(ctx, job_id)
     }
 
+    pub fn close_job(
+        ctx: Context<CloseJob>,
+        job_id: u64
+    ) -> Result<()> {
+        job::close_job(ctx, job_id)
+    }
+
+    pub fn expire_job(
+        ctx: Context<ExpireJob>,
+        job_id: u64
+    ) -> Result<()> {
+        job::expire_job(ctx, job_id)
+    }
+
+    pub fn accept_contract(
+        ctx: Context<AcceptContract>,
+        job_id: u64
+    ) -> Result<()> {
+        job::accept_contract(ctx, job_id)
+    }
+
+    pub fn submit_work(
+        ctx: Context<SubmitWork>,
+        job_id: u64,
+        deliverable_hash: [u8; 32]
+    ) -> Result<()> {
+        job::submit_work(ctx, job_id, deliverable_hash)
+    }
+
+    pub fn request_work_revision(
+        ctx: Context<RequestWorkRevision>,
+        job_id: u64
+    ) -> Result<()> {
+        job::request_work_revision(ctx, job_id)
+    }
+
+    pub fn submit_milestone(
+        ctx: Context<SubmitMilestone>,
+        job_id: u64,
+        milestone_index: u8,
+        deliverable_hash: [u8; 32]
+    ) -> Result<()> {
+        job::submit_milestone(ctx, job_id, milestone_index, deliverable_hash)
+    }
+
+    pub fn request_milestone_revision(
+        ctx: Context<RequestMilestoneRevision>,
+        job_id: u64,
+        milestone_index: u8
+    ) -> Result<()> {
+        job::request_milestone_revision(ctx, job_id, milestone_index)
+    }
+
     // Escrow Module Functions
     pub fn create_escrow(
         ctx: Context<CreateEscrow>,
         amount: u64,
         token_mint: Option<Pubkey>,
+        initial_deposit: u64,
+    ) -> Result<()> {
+        escrow::create_escrow(ctx, amount, token_mint, initial_deposit)
+    }
+
+    pub fn fund_escrow(
+        ctx: Context<FundEscrow>,
+        job_id: u64,
+        amount: u64
     ) -> Result<()> {
-        escrow::create_escrow(ctx, amount, token_mint)
+        escrow::fund_escrow(ctx, job_id, amount)
     }
 
     pub fn release_escrow(
@@ -102,6 +280,14 @@ pub mod freelance_marketplace {
         escrow::milestone_payment(ctx, job_id, milestone_index)
     }
 
+    pub fn claim_after_timeout(
+        ctx: Context<ClaimAfterTimeout>,
+        job_id: u64,
+        milestone_index: Option<u8>
+    ) -> Result<()> {
+        escrow::claim_after_timeout(ctx, job_id, milestone_index)
+    }
+
     pub fn refund_escrow(
         ctx: Context<RefundEscrow>,
         job_id: u64
@@ -109,6 +295,28 @@ pub mod freelance_marketplace {
         escrow::refund_escrow(ctx, job_id)
     }
 
+    pub fn propose_cancellation(
+        ctx: Context<ProposeCancellation>,
+        job_id: u64,
+        freelancer_bps: u16
+    ) -> Result<()> {
+        escrow::propose_cancellation(ctx, job_id, freelancer_bps)
+    }
+
+    pub fn accept_cancellation(
+        ctx: Context<AcceptCancellation>,
+        job_id: u64,
+        freelancer_bps: u16
+    ) -> Result<()> {
+        escrow::accept_cancellation(ctx, job_id, freelancer_bps)
+    }
+
+    pub fn close_escrow(
+        ctx: Context<CloseEscrow>
+    ) -> Result<()> {
+        escrow::close_escrow(ctx)
+    }
+
     // Profile Module Functions
     pub fn create_profile(
         ctx: Context<CreateProfile>,
@@ -133,24 +341,102 @@ pub mod freelance_marketplace {
     }
 
     // Dispute Module Functions
-    pub fn create_dispute(
-        ctx: Context<CreateDispute>,
+    pub fn initiate_dispute(
+        ctx: Context<InitiateDispute>,
         job_id: u64,
-        reason: String,
-        evidence: String
+        milestone_index: Option<u8>,
+        reason: String
+    ) -> Result<()> {
+        dispute::initiate_dispute(ctx, job_id, milestone_index, reason)
+    }
+
+    pub fn submit_evidence(
+        ctx: Context<SubmitEvidence>,
+        index: u8,
+        content_hash: [u8; 32],
+        uri: String,
+        description: String
     ) -> Result<()> {
-        dispute::create_dispute(ctx, job_id, reason, evidence)
+        dispute::submit_evidence(ctx, index, content_hash, uri, description)
+    }
+
+    pub fn claim_default_judgment(ctx: Context<ClaimDefaultJudgment>) -> Result<()> {
+        dispute::claim_default_judgment(ctx)
     }
 
     pub fn resolve_dispute(
         ctx: Context<ResolveDispute>,
-        dispute_id: u64,
         resolution_type: ResolutionType,
-        resolution_note: String,
-        client_percentage: u8,
-        freelancer_percentage: u8
+        split_bps: u16,
+        resolution_note: String
     ) -> Result<()> {
-        dispute::resolve_dispute(ctx, dispute_id, resolution_type, resolution_note, client_percentage, freelancer_percentage)
+        dispute::resolve_dispute(ctx, resolution_type, split_bps, resolution_note)
+    }
+
+    pub fn propose_settlement(ctx: Context<ProposeSettlement>, freelancer_bps: u16) -> Result<()> {
+        dispute::propose_settlement(ctx, freelancer_bps)
+    }
+
+    pub fn accept_settlement(ctx: Context<AcceptSettlement>, freelancer_bps: u16) -> Result<()> {
+        dispute::accept_settlement(ctx, freelancer_bps)
+    }
+
+    pub fn appeal_ruling(ctx: Context<AppealRuling>) -> Result<()> {
+        dispute::appeal_ruling(ctx)
+    }
+
+    pub fn execute_ruling(ctx: Context<ExecuteRuling>) -> Result<()> {
+        dispute::execute_ruling(ctx)
+    }
+
+    // Jury Module Functions
+    pub fn initialize_juror_pool(ctx: Context<InitializeJurorPool>) -> Result<()> {
+        jury::initialize_juror_pool(ctx)
+    }
+
+    pub fn register_juror(ctx: Context<RegisterJuror>, stake: u64) -> Result<()> {
+        jury::register_juror(ctx, stake)
+    }
+
+    pub fn unregister_juror(ctx: Context<UnregisterJuror>) -> Result<()> {
+        jury::unregister_juror(ctx)
+    }
+
+    pub fn remove_juror(ctx: Context<RemoveJuror>) -> Result<()> {
+        jury::remove_juror(ctx)
+    }
+
+    pub fn withdraw_juror_stake(ctx: Context<WithdrawJurorStake>) -> Result<()> {
+        jury::withdraw_juror_stake(ctx)
+    }
+
+    pub fn request_jury(ctx: Context<RequestJury>) -> Result<()> {
+        jury::request_jury(ctx)
+    }
+
+    pub fn commit_vote(ctx: Context<CastVote>, commitment: [u8; 32]) -> Result<()> {
+        jury::commit_vote(ctx, commitment)
+    }
+
+    pub fn reveal_vote(
+        ctx: Context<CastVote>,
+        resolution_type: ResolutionType,
+        split_bps: u16,
+        salt: [u8; 32]
+    ) -> Result<()> {
+        jury::reveal_vote(ctx, resolution_type, split_bps, salt)
+    }
+
+    pub fn finalize_jury(ctx: Context<FinalizeJury>) -> Result<()> {
+        jury::finalize_jury(ctx)
+    }
+
+    pub fn claim_juror_fee(ctx: Context<ClaimJurorFee>) -> Result<()> {
+        jury::claim_juror_fee(ctx)
+    }
+
+    pub fn slash_juror(ctx: Context<SlashJuror>) -> Result<()> {
+        jury::slash_juror(ctx)
     }
 
     // Portfolio Module Functions
@@ -19,7 +19,8 @@ pub struct CreateReview<'info> {
     pub reviewer: Signer<'info>,
     #[account(
         mut,
-        constraint = job.id == job_id,
+        seeds = [b"job", job.client.as_ref(), &job_id.to_le_bytes()],
+        bump = job.bump,
         constraint = job.status == JobStatus::Completed,
         constraint = job.client == reviewer.key() || job.freelancer.unwrap() == reviewer.key()
     )]